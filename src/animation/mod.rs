@@ -0,0 +1,9 @@
+//! Reusable animation building blocks for characters.
+
+use bevy::prelude::*;
+
+pub(crate) mod state_machine;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(state_machine::plugin);
+}
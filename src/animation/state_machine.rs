@@ -0,0 +1,744 @@
+//! Animation state machines are assets that describe the animations of a character as data.
+//!
+//! A state machine is loaded from an `.anim.ron` file listing states, the clip or blend space each state plays,
+//! and the transitions between states together with their conditions and blend durations.
+//! A `BlendSpace` blends clips along one parameter, a `BlendSpace2d` along two, e.g. speed and turn rate.
+//! Gameplay code only sets parameters on the character's [`AnimationController`], and [`drive_state_machines`]
+//! takes care of playing the right clips. Editing the file while the game is running reloads it in dev builds.
+//!
+//! ```ron
+//! (
+//!     initial: "idle",
+//!     states: [
+//!         (name: "idle", motion: Clip(clip: "models/fox/Fox.gltf#Animation1")),
+//!         (name: "jump", motion: Clip(clip: "models/fox/Fox.gltf#Animation0", looping: false)),
+//!     ],
+//!     transitions: [
+//!         (from: Some("idle"), to: "jump", when: [IsFalse("grounded")], blend_secs: 0.2),
+//!         (from: Some("jump"), to: "idle", when: [Finished], blend_secs: 0.5),
+//!     ],
+//! )
+//! ```
+
+use std::time::Duration;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    platform::collections::HashMap,
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::{PostPhysicsAppSystems, gameplay::animation::AnimationPlayers};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<AnimationStateMachine>();
+    app.init_asset_loader::<AnimationStateMachineLoader>();
+    app.add_systems(
+        Update,
+        (rebuild_reloaded_state_machines, drive_state_machines)
+            .chain()
+            .in_set(PostPhysicsAppSystems::PlayAnimations),
+    );
+}
+
+/// Plays the animations of a character according to an [`AnimationStateMachine`].
+/// Add this next to an [`AnimationPlayerAncestor`](crate::gameplay::animation::AnimationPlayerAncestor),
+/// then set the parameters the state machine's conditions and blend spaces refer to.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub(crate) struct AnimationController {
+    machine: Handle<AnimationStateMachine>,
+    parameters: HashMap<String, f32>,
+}
+
+impl AnimationController {
+    pub(crate) fn new(machine: Handle<AnimationStateMachine>) -> Self {
+        Self {
+            machine,
+            parameters: default(),
+        }
+    }
+
+    pub(crate) fn set_float(&mut self, name: &str, value: f32) {
+        match self.parameters.get_mut(name) {
+            Some(parameter) => *parameter = value,
+            None => {
+                self.parameters.insert(name.to_string(), value);
+            }
+        }
+    }
+
+    pub(crate) fn set_bool(&mut self, name: &str, value: bool) {
+        self.set_float(name, if value { 1.0 } else { 0.0 });
+    }
+
+    /// Parameters that were never set are zero, i.e. `false`.
+    fn get(&self, name: &str) -> f32 {
+        self.parameters.get(name).copied().unwrap_or_default()
+    }
+}
+
+/// States, the animations they play, and the transitions between them.
+#[derive(Asset, TypePath, Debug)]
+pub(crate) struct AnimationStateMachine {
+    /// The motion of each state.
+    states: Vec<Motion>,
+    transitions: Vec<Transition>,
+    initial: usize,
+}
+
+#[derive(Debug)]
+enum Motion {
+    Clip {
+        clip: Handle<AnimationClip>,
+        looping: bool,
+    },
+    BlendSpace {
+        /// The parameters along the x and the optional y axis.
+        parameters: (String, Option<String>),
+        stride_parameter: Option<String>,
+        clips: Vec<(Handle<AnimationClip>, Vec2, Option<f32>)>,
+    },
+}
+
+#[derive(Debug)]
+struct Transition {
+    /// `None` means that the transition can be taken from any state.
+    from: Option<usize>,
+    to: usize,
+    when: Vec<Condition>,
+    duration: Duration,
+}
+
+/// A condition for taking a transition. All conditions of a transition have to hold.
+#[derive(Deserialize, Debug, Clone)]
+enum Condition {
+    Above(String, f32),
+    Below(String, f32),
+    IsTrue(String),
+    IsFalse(String),
+    /// The clip of the current state finished playing. Only makes sense for clips that don't loop.
+    Finished,
+}
+
+#[derive(Deserialize)]
+struct StateMachineDefinition {
+    initial: String,
+    states: Vec<StateDefinition>,
+    transitions: Vec<TransitionDefinition>,
+}
+
+#[derive(Deserialize)]
+struct StateDefinition {
+    name: String,
+    motion: MotionDefinition,
+}
+
+#[derive(Deserialize)]
+enum MotionDefinition {
+    Clip {
+        clip: String,
+        #[serde(default = "looping_by_default")]
+        looping: bool,
+    },
+    BlendSpace {
+        parameter: String,
+        /// The parameter the stride-matched clips are sped up or slowed down by.
+        #[serde(default)]
+        stride_parameter: Option<String>,
+        clips: Vec<BlendSpaceClipDefinition>,
+    },
+    BlendSpace2d {
+        /// The parameters along the x and y axes of the blend space.
+        parameters: (String, String),
+        /// The parameter the stride-matched clips are sped up or slowed down by.
+        #[serde(default)]
+        stride_parameter: Option<String>,
+        clips: Vec<BlendSpace2dClipDefinition>,
+    },
+}
+
+fn looping_by_default() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct BlendSpaceClipDefinition {
+    clip: String,
+    position: f32,
+    #[serde(default)]
+    stride_speed: Option<f32>,
+}
+
+#[derive(Deserialize)]
+struct BlendSpace2dClipDefinition {
+    clip: String,
+    position: (f32, f32),
+    #[serde(default)]
+    stride_speed: Option<f32>,
+}
+
+#[derive(Deserialize)]
+struct TransitionDefinition {
+    #[serde(default)]
+    from: Option<String>,
+    to: String,
+    #[serde(default)]
+    when: Vec<Condition>,
+    blend_secs: f32,
+}
+
+impl StateMachineDefinition {
+    /// Turns the state names into indices and loads the clips with `load_clip`.
+    fn resolve(
+        self,
+        mut load_clip: impl FnMut(String) -> Handle<AnimationClip>,
+    ) -> Result<AnimationStateMachine, String> {
+        let state_index = |name: &str| {
+            self.states
+                .iter()
+                .position(|state| state.name == name)
+                .ok_or_else(|| format!("There is no state named \"{name}\""))
+        };
+        let initial = state_index(&self.initial)?;
+        let transitions = self
+            .transitions
+            .iter()
+            .map(|transition| {
+                let duration =
+                    Duration::try_from_secs_f32(transition.blend_secs).map_err(|_| {
+                        format!(
+                            "The transition to \"{}\" has an invalid `blend_secs` of {}",
+                            transition.to, transition.blend_secs
+                        )
+                    })?;
+                Ok(Transition {
+                    from: transition.from.as_deref().map(state_index).transpose()?,
+                    to: state_index(&transition.to)?,
+                    when: transition.when.clone(),
+                    duration,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        let states = self
+            .states
+            .into_iter()
+            .map(|state| match state.motion {
+                MotionDefinition::Clip { clip, looping } => Motion::Clip {
+                    clip: load_clip(clip),
+                    looping,
+                },
+                MotionDefinition::BlendSpace {
+                    parameter,
+                    stride_parameter,
+                    clips,
+                } => Motion::BlendSpace {
+                    parameters: (parameter, None),
+                    stride_parameter,
+                    clips: clips
+                        .into_iter()
+                        .map(|clip| {
+                            let position = Vec2::new(clip.position, 0.0);
+                            (load_clip(clip.clip), position, clip.stride_speed)
+                        })
+                        .collect(),
+                },
+                MotionDefinition::BlendSpace2d {
+                    parameters: (x, y),
+                    stride_parameter,
+                    clips,
+                } => Motion::BlendSpace {
+                    parameters: (x, Some(y)),
+                    stride_parameter,
+                    clips: clips
+                        .into_iter()
+                        .map(|clip| {
+                            let position = Vec2::from(clip.position);
+                            (load_clip(clip.clip), position, clip.stride_speed)
+                        })
+                        .collect(),
+                },
+            })
+            .collect();
+
+        Ok(AnimationStateMachine {
+            states,
+            transitions,
+            initial,
+        })
+    }
+}
+
+#[derive(Default, TypePath)]
+struct AnimationStateMachineLoader;
+
+impl AssetLoader for AnimationStateMachineLoader {
+    type Asset = AnimationStateMachine;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let definition: StateMachineDefinition = ron::de::from_bytes(&bytes)?;
+        let machine = definition.resolve(|clip| load_context.load(clip))?;
+        Ok(machine)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["anim.ron"]
+    }
+}
+
+/// The state machine of a single [`AnimationPlayer`], built from an [`AnimationStateMachine`].
+#[derive(Component)]
+struct StateMachineInstance {
+    machine: AssetId<AnimationStateMachine>,
+    states: Vec<StateInstance>,
+    current: usize,
+    /// How much each state contributes to the pose. Only the current state fades in, all others fade out.
+    weights: Vec<f32>,
+    /// How much the weights change per second during the current transition.
+    fade_per_sec: f32,
+}
+
+enum StateInstance {
+    Clip {
+        node: AnimationNodeIndex,
+        looping: bool,
+    },
+    BlendSpace {
+        blend_space: BlendSpace,
+        parameters: (String, Option<String>),
+        stride_parameter: Option<String>,
+    },
+}
+
+/// Blends looping clips by one or two continuous parameters, e.g. the horizontal speed and the turn rate
+/// of a character, instead of switching between them. Each clip has full weight at its position in the blend space,
+/// and neighboring clips are cross-faded in between.
+#[derive(Debug, Default)]
+struct BlendSpace {
+    /// Sorted by [`BlendSpaceClip::position`], first along x, then along y.
+    clips: Vec<BlendSpaceClip>,
+    /// The current weights of the clips, following the target weights smoothly.
+    weights: Vec<f32>,
+    /// The position in the blend space. One-dimensional blend spaces only use x.
+    parameter: Vec2,
+    movement_speed: f32,
+}
+
+#[derive(Debug)]
+struct BlendSpaceClip {
+    node: AnimationNodeIndex,
+    position: Vec2,
+    /// The movement speed at which the clip plays at normal speed, so that the feet don't slide.
+    stride_speed: Option<f32>,
+}
+
+/// Clips that are matched to the movement speed never play slower than this, so that characters don't freeze mid-step.
+const MIN_STRIDE_PLAYBACK_SPEED: f32 = 0.3;
+
+impl BlendSpace {
+    fn with_clip(mut self, clip: BlendSpaceClip) -> Self {
+        let index = self.clips.partition_point(|other| {
+            (other.position.x, other.position.y) <= (clip.position.x, clip.position.y)
+        });
+        self.clips.insert(index, clip);
+        self.weights.insert(index, 0.0);
+        self
+    }
+
+    /// The weights the clips should have for the current parameter.
+    /// Uses gradient band interpolation: the weight of a clip falls off linearly towards each other clip,
+    /// and the weights are normalized afterwards. Along a single axis, this cross-fades neighboring clips.
+    fn target_weights(&self) -> Vec<f32> {
+        let mut weights: Vec<f32> = self
+            .clips
+            .iter()
+            .map(|clip| {
+                let to_parameter = self.parameter - clip.position;
+                self.clips
+                    .iter()
+                    .filter_map(|other| {
+                        let to_other = other.position - clip.position;
+                        let distance_squared = to_other.length_squared();
+                        (distance_squared > 0.0)
+                            .then(|| 1.0 - to_parameter.dot(to_other) / distance_squared)
+                    })
+                    .fold(1.0, f32::min)
+                    .max(0.0)
+            })
+            .collect();
+        let total: f32 = weights.iter().sum();
+        if total > 0.0 {
+            for weight in &mut weights {
+                *weight /= total;
+            }
+        }
+        weights
+    }
+
+    /// Plays all clips of the blend space on `player`, with their weights scaled by `weight`.
+    fn animate(&mut self, player: &mut AnimationPlayer, weight: f32, delta_secs: f32) {
+        // Smooth out sudden parameter changes, e.g. when a character is stopped by a wall.
+        let decay_rate = f32::ln(1_000.0);
+        let target_weights = self.target_weights();
+        for ((clip, clip_weight), target) in
+            self.clips.iter().zip(&mut self.weights).zip(target_weights)
+        {
+            clip_weight.smooth_nudge(&target, decay_rate, delta_secs);
+            let speed = clip.stride_speed.map_or(1.0, |stride_speed| {
+                (self.movement_speed / stride_speed).max(MIN_STRIDE_PLAYBACK_SPEED)
+            });
+            // All clips keep playing, even with zero weight, so that they don't restart when they are blended in.
+            if !player.is_playing_animation(clip.node) {
+                player.play(clip.node).repeat();
+            }
+            if let Some(animation) = player.animation_mut(clip.node) {
+                animation.set_weight(*clip_weight * weight).set_speed(speed);
+            }
+        }
+    }
+}
+
+impl StateMachineInstance {
+    fn new(
+        id: AssetId<AnimationStateMachine>,
+        machine: &AnimationStateMachine,
+        graph: &mut AnimationGraph,
+    ) -> Self {
+        let root = graph.root;
+        let states = machine
+            .states
+            .iter()
+            .map(|motion| match motion {
+                Motion::Clip { clip, looping } => StateInstance::Clip {
+                    node: graph.add_clip(clip.clone(), 1.0, root),
+                    looping: *looping,
+                },
+                Motion::BlendSpace {
+                    parameters,
+                    stride_parameter,
+                    clips,
+                } => StateInstance::BlendSpace {
+                    blend_space: clips.iter().fold(
+                        BlendSpace::default(),
+                        |blend_space, (clip, position, stride_speed)| {
+                            blend_space.with_clip(BlendSpaceClip {
+                                node: graph.add_clip(clip.clone(), 1.0, root),
+                                position: *position,
+                                stride_speed: *stride_speed,
+                            })
+                        },
+                    ),
+                    parameters: parameters.clone(),
+                    stride_parameter: stride_parameter.clone(),
+                },
+            })
+            .collect::<Vec<_>>();
+        let mut weights = vec![0.0; states.len()];
+        weights[machine.initial] = 1.0;
+        Self {
+            machine: id,
+            states,
+            current: machine.initial,
+            weights,
+            fade_per_sec: f32::INFINITY,
+        }
+    }
+
+    fn condition_holds(
+        &self,
+        condition: &Condition,
+        controller: &AnimationController,
+        player: &AnimationPlayer,
+    ) -> bool {
+        match condition {
+            Condition::Above(name, value) => controller.get(name) > *value,
+            Condition::Below(name, value) => controller.get(name) < *value,
+            Condition::IsTrue(name) => controller.get(name) != 0.0,
+            Condition::IsFalse(name) => controller.get(name) == 0.0,
+            Condition::Finished => match &self.states[self.current] {
+                StateInstance::Clip { node, .. } => player
+                    .animation(*node)
+                    .is_none_or(|animation| animation.is_finished()),
+                StateInstance::BlendSpace { .. } => false,
+            },
+        }
+    }
+
+    fn enter(&mut self, state: usize, duration: Duration, player: &mut AnimationPlayer) {
+        self.current = state;
+        self.fade_per_sec = 1.0 / duration.as_secs_f32();
+        // Clips start from the beginning when their state is entered, blend spaces just keep going.
+        if let StateInstance::Clip { node, looping } = &self.states[state] {
+            let animation = player.start(*node);
+            if *looping {
+                animation.repeat();
+            }
+        }
+    }
+}
+
+/// Rebuilds the state machines of all characters using a state machine asset that was changed, e.g. by hot reloading.
+/// The animation graph built for the old version is removed together with the instance.
+fn rebuild_reloaded_state_machines(
+    mut commands: Commands,
+    mut events: MessageReader<AssetEvent<AnimationStateMachine>>,
+    mut instances: Query<(
+        Entity,
+        &StateMachineInstance,
+        &mut AnimationPlayer,
+        &AnimationGraphHandle,
+    )>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+) {
+    for event in events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        for (entity, instance, mut player, graph) in &mut instances {
+            if instance.machine == *id {
+                player.stop_all();
+                graphs.remove(graph);
+                commands
+                    .entity(entity)
+                    .remove::<(StateMachineInstance, AnimationGraphHandle)>();
+            }
+        }
+    }
+}
+
+/// Evaluates the transitions of every state machine and plays the animations of its states.
+fn drive_state_machines(
+    mut commands: Commands,
+    controllers: Query<(&AnimationController, &AnimationPlayers)>,
+    mut players: Query<(
+        Entity,
+        &mut AnimationPlayer,
+        Option<&mut StateMachineInstance>,
+    )>,
+    machines: Res<Assets<AnimationStateMachine>>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+    time: Res<Time>,
+) {
+    let delta_secs = time.delta_secs();
+    for (controller, anim_players) in &controllers {
+        let Some(machine) = machines.get(&controller.machine) else {
+            continue;
+        };
+        let mut iter = players.iter_many_mut(anim_players.iter());
+        while let Some((entity, mut player, instance)) = iter.fetch_next() {
+            let Some(mut instance) = instance else {
+                let mut graph = AnimationGraph::new();
+                let mut instance =
+                    StateMachineInstance::new(controller.machine.id(), machine, &mut graph);
+                let initial = instance.current;
+                instance.enter(initial, Duration::ZERO, &mut player);
+                commands
+                    .entity(entity)
+                    .insert((instance, AnimationGraphHandle(graphs.add(graph))));
+                continue;
+            };
+
+            let transition = machine.transitions.iter().find(|transition| {
+                transition.to != instance.current
+                    && transition.from.is_none_or(|from| from == instance.current)
+                    && transition
+                        .when
+                        .iter()
+                        .all(|condition| instance.condition_holds(condition, controller, &player))
+            });
+            if let Some(transition) = transition {
+                instance.enter(transition.to, transition.duration, &mut player);
+            }
+
+            let instance = &mut *instance;
+            let fade = instance.fade_per_sec * delta_secs;
+            for (index, (state, weight)) in instance
+                .states
+                .iter_mut()
+                .zip(&mut instance.weights)
+                .enumerate()
+            {
+                let target = if index == instance.current { 1.0 } else { 0.0 };
+                *weight = if fade.is_finite() {
+                    *weight + (target - *weight).clamp(-fade, fade)
+                } else {
+                    target
+                };
+                match state {
+                    StateInstance::Clip { node, .. } => {
+                        if let Some(animation) = player.animation_mut(*node) {
+                            animation.set_weight(*weight);
+                        }
+                    }
+                    StateInstance::BlendSpace {
+                        blend_space,
+                        parameters: (x, y),
+                        stride_parameter,
+                    } => {
+                        blend_space.parameter = Vec2::new(
+                            controller.get(x),
+                            y.as_deref().map_or(0.0, |y| controller.get(y)),
+                        );
+                        if let Some(stride_parameter) = stride_parameter {
+                            blend_space.movement_speed = controller.get(stride_parameter);
+                        }
+                        blend_space.animate(&mut player, *weight, delta_secs);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(ron: &str) -> Result<AnimationStateMachine, String> {
+        let definition: StateMachineDefinition = ron::from_str(ron).unwrap();
+        definition.resolve(|_| Handle::default())
+    }
+
+    #[test]
+    fn resolves_state_names() {
+        let machine = resolve(
+            r#"(
+                initial: "idle",
+                states: [
+                    (name: "jump", motion: Clip(clip: "jump.glb#Animation0", looping: false)),
+                    (name: "idle", motion: BlendSpace(
+                        parameter: "speed",
+                        clips: [(clip: "idle.glb#Animation0", position: 0.0)],
+                    )),
+                ],
+                transitions: [
+                    (from: Some("idle"), to: "jump", when: [IsFalse("grounded")], blend_secs: 0.5),
+                    (to: "idle", when: [Finished], blend_secs: 0.0),
+                ],
+            )"#,
+        )
+        .unwrap();
+        assert_eq!(machine.initial, 1);
+        assert_eq!(machine.states.len(), 2);
+        assert!(matches!(
+            machine.states[0],
+            Motion::Clip { looping: false, .. }
+        ));
+        assert_eq!(machine.transitions[0].from, Some(1));
+        assert_eq!(machine.transitions[0].to, 0);
+        assert_eq!(machine.transitions[0].duration, Duration::from_millis(500));
+        assert_eq!(machine.transitions[1].from, None);
+        assert_eq!(machine.transitions[1].duration, Duration::ZERO);
+    }
+
+    #[test]
+    fn rejects_unknown_states() {
+        let states = r#"[(name: "idle", motion: Clip(clip: "idle.glb#Animation0"))]"#;
+        assert!(
+            resolve(&format!(
+                "(initial: \"run\", states: {states}, transitions: [])"
+            ))
+            .is_err()
+        );
+        assert!(
+            resolve(&format!(
+                "(initial: \"idle\", states: {states}, transitions: [(to: \"run\", blend_secs: 0.1)])"
+            ))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_blend_durations() {
+        let states = r#"[(name: "idle", motion: Clip(clip: "idle.glb#Animation0"))]"#;
+        for blend_secs in ["-0.1", "1e30"] {
+            let ron = format!(
+                "(initial: \"idle\", states: {states}, transitions: [(to: \"idle\", blend_secs: {blend_secs})])"
+            );
+            assert!(resolve(&ron).is_err(), "{blend_secs}");
+        }
+    }
+
+    #[test]
+    fn blend_space_cross_fades_neighboring_clips() {
+        let clip = |index, position| BlendSpaceClip {
+            node: AnimationNodeIndex::new(index),
+            position: Vec2::new(position, 0.0),
+            stride_speed: None,
+        };
+        // Clips are sorted by their position, no matter in which order they are added.
+        let mut blend_space = BlendSpace::default()
+            .with_clip(clip(2, 6.0))
+            .with_clip(clip(0, 0.0))
+            .with_clip(clip(1, 2.0));
+
+        blend_space.parameter = Vec2::new(-1.0, 0.0);
+        assert_eq!(blend_space.target_weights(), [1.0, 0.0, 0.0]);
+        blend_space.parameter = Vec2::new(1.0, 0.0);
+        assert_eq!(blend_space.target_weights(), [0.5, 0.5, 0.0]);
+        blend_space.parameter = Vec2::new(5.0, 0.0);
+        assert_eq!(blend_space.target_weights(), [0.0, 0.25, 0.75]);
+        blend_space.parameter = Vec2::new(10.0, 0.0);
+        assert_eq!(blend_space.target_weights(), [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn blend_space_2d_blends_along_both_axes() {
+        let clip = |index, x, y| BlendSpaceClip {
+            node: AnimationNodeIndex::new(index),
+            position: Vec2::new(x, y),
+            stride_speed: None,
+        };
+        let mut blend_space = BlendSpace::default()
+            .with_clip(clip(0, 0.0, 0.0))
+            .with_clip(clip(1, 0.0, 2.0))
+            .with_clip(clip(2, 2.0, 0.0));
+
+        blend_space.parameter = Vec2::ZERO;
+        assert_eq!(blend_space.target_weights(), [1.0, 0.0, 0.0]);
+        blend_space.parameter = Vec2::new(1.0, 0.0);
+        assert_eq!(blend_space.target_weights(), [0.5, 0.0, 0.5]);
+        blend_space.parameter = Vec2::new(0.0, 1.0);
+        assert_eq!(blend_space.target_weights(), [0.5, 0.5, 0.0]);
+        blend_space.parameter = Vec2::new(0.0, 5.0);
+        assert_eq!(blend_space.target_weights(), [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn resolves_2d_blend_spaces() {
+        let machine = resolve(
+            r#"(
+                initial: "locomotion",
+                states: [
+                    (name: "locomotion", motion: BlendSpace2d(
+                        parameters: ("speed", "turn_rate"),
+                        clips: [
+                            (clip: "idle.glb#Animation0", position: (0.0, 0.0)),
+                            (clip: "walk.glb#Animation0", position: (0.0, 3.0)),
+                        ],
+                    )),
+                ],
+                transitions: [],
+            )"#,
+        )
+        .unwrap();
+        let Motion::BlendSpace {
+            parameters, clips, ..
+        } = &machine.states[0]
+        else {
+            panic!("Expected a blend space");
+        };
+        assert_eq!(parameters, &("speed".into(), Some("turn_rate".into())));
+        assert_eq!(clips[1].1, Vec2::new(0.0, 3.0));
+    }
+}
@@ -0,0 +1,256 @@
+//! Closed captions for sounds. A sample player with a [`Caption`] shows its caption on screen while it plays, together
+//! with an arrow pointing from the camera towards the sound. Sounds that are too far away to be heard well are not
+//! captioned. Players can turn captions off or change their size in the settings.
+
+use std::time::Duration;
+
+use bevy::{platform::collections::HashMap, prelude::*, ui::Val::*};
+use bevy_seedling::prelude::*;
+
+use crate::{
+    PostPhysicsAppSystems, audio::SpatialPool, gameplay::player::camera::PlayerCamera,
+    screens::Screen, theme::palette::LABEL_TEXT,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CaptionSettings>();
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_caption_display);
+    app.add_systems(
+        Update,
+        update_captions
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PostPhysicsAppSystems::ChangeUi),
+    );
+}
+
+/// Describes the sound of a sample player for captions, e.g. "[fire crackling]".
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub(crate) struct Caption {
+    pub(crate) text: String,
+    /// Spatial sounds further away from the camera than this are not captioned, in meters.
+    pub(crate) range: f32,
+}
+
+impl Caption {
+    pub(crate) fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            range: 15.0,
+        }
+    }
+
+    pub(crate) fn with_range(mut self, range: f32) -> Self {
+        self.range = range;
+        self
+    }
+}
+
+#[derive(Resource, Debug, Default, Reflect)]
+#[reflect(Resource)]
+pub(crate) struct CaptionSettings {
+    pub(crate) size: CaptionSize,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+pub(crate) enum CaptionSize {
+    Off,
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl CaptionSize {
+    const ALL: [Self; 4] = [Self::Off, Self::Small, Self::Medium, Self::Large];
+
+    pub(crate) fn smaller(self) -> Self {
+        let index = Self::ALL.iter().position(|size| *size == self).unwrap_or(0);
+        Self::ALL[index.saturating_sub(1)]
+    }
+
+    pub(crate) fn larger(self) -> Self {
+        let index = Self::ALL.iter().position(|size| *size == self).unwrap_or(0);
+        Self::ALL[(index + 1).min(Self::ALL.len() - 1)]
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Small => "Small",
+            Self::Medium => "Medium",
+            Self::Large => "Large",
+        }
+    }
+
+    fn font_size(self) -> f32 {
+        match self {
+            Self::Off => 0.0,
+            Self::Small => 18.0,
+            Self::Medium => 24.0,
+            Self::Large => 32.0,
+        }
+    }
+}
+
+/// How long a caption stays after its sound stopped, so that short, repeated sounds like footsteps don't flicker.
+const CAPTION_LINGER: Duration = Duration::from_millis(1200);
+/// Sounds closer than this have no direction arrow, in meters.
+const MIN_DIRECTION_DISTANCE: f32 = 1.0;
+
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct CaptionDisplay;
+
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct CaptionRow(String);
+
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct CaptionArrow;
+
+/// A caption currently on screen.
+#[derive(Debug)]
+struct ActiveCaption {
+    /// Clockwise angle from the camera's forward direction towards the sound. `None` for sounds without a direction.
+    direction: Option<f32>,
+    last_heard: Duration,
+}
+
+fn spawn_caption_display(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Caption Display"),
+        CaptionDisplay,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Px(20.0),
+            bottom: Percent(30.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Px(4.0),
+            ..default()
+        },
+        DespawnOnExit(Screen::Gameplay),
+        Pickable::IGNORE,
+    ));
+}
+
+fn update_captions(
+    mut commands: Commands,
+    display: Single<(Entity, &mut Visibility), With<CaptionDisplay>>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    sounds: Query<(&Caption, Option<&GlobalTransform>, Has<SpatialPool>), With<SamplePlayer>>,
+    rows: Query<(Entity, &CaptionRow, &Children)>,
+    mut arrows: Query<
+        (&mut UiTransform, &mut Visibility),
+        (With<CaptionArrow>, Without<CaptionDisplay>),
+    >,
+    mut texts: Query<&mut TextFont>,
+    settings: Res<CaptionSettings>,
+    time: Res<Time>,
+    mut active: Local<HashMap<String, ActiveCaption>>,
+) {
+    let (display, mut display_visibility) = display.into_inner();
+    if settings.size == CaptionSize::Off {
+        *display_visibility = Visibility::Hidden;
+        active.clear();
+        return;
+    }
+    *display_visibility = Visibility::Inherited;
+
+    let now = time.elapsed();
+    let camera_transform = camera.compute_transform();
+    let world_to_camera = camera.affine().inverse();
+    for (caption, transform, is_spatial) in &sounds {
+        let direction = match transform {
+            Some(transform) if is_spatial => {
+                let position = transform.translation();
+                if position.distance(camera_transform.translation) > caption.range {
+                    continue;
+                }
+                let local = world_to_camera.transform_point3(position);
+                (local.xz().length() >= MIN_DIRECTION_DISTANCE).then(|| local.x.atan2(-local.z))
+            }
+            _ => None,
+        };
+        let entry = active.entry(caption.text.clone()).or_insert(ActiveCaption {
+            direction,
+            last_heard: now,
+        });
+        entry.direction = direction;
+        entry.last_heard = now;
+    }
+    active.retain(|_, caption| now.saturating_sub(caption.last_heard) <= CAPTION_LINGER);
+
+    let font_size = settings.size.font_size();
+    for (row, CaptionRow(text), children) in &rows {
+        let Some(caption) = active.get(text) else {
+            commands.entity(row).despawn();
+            continue;
+        };
+        for child in children.iter() {
+            if let Ok(mut font) = texts.get_mut(child) {
+                font.font_size = font_size;
+            }
+            if let Ok((mut transform, mut visibility)) = arrows.get_mut(child) {
+                match caption.direction {
+                    Some(angle) => {
+                        transform.rotation = Rot2::radians(angle);
+                        *visibility = Visibility::Inherited;
+                    }
+                    None => *visibility = Visibility::Hidden,
+                }
+            }
+        }
+    }
+
+    let shown: Vec<_> = rows.iter().map(|(_, CaptionRow(text), _)| text).collect();
+    for text in active.keys().filter(|text| !shown.contains(text)) {
+        commands.entity(display).with_child((
+            Name::new("Caption"),
+            CaptionRow(text.clone()),
+            Node {
+                align_items: AlignItems::Center,
+                column_gap: Px(8.0),
+                padding: UiRect::axes(Px(8.0), Px(2.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            children![
+                (
+                    // Points up for sounds in front of the camera and is rotated clockwise towards the sound.
+                    Text::new("^"),
+                    TextFont::from_font_size(font_size),
+                    TextColor(LABEL_TEXT),
+                    CaptionArrow,
+                    UiTransform::default(),
+                    Visibility::Hidden,
+                ),
+                (
+                    Text(text.clone()),
+                    TextFont::from_font_size(font_size),
+                    TextColor(LABEL_TEXT),
+                ),
+            ],
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caption_size_steps_through_sizes() {
+        assert_eq!(CaptionSize::Medium.smaller(), CaptionSize::Small);
+        assert_eq!(CaptionSize::Small.smaller(), CaptionSize::Off);
+        assert_eq!(CaptionSize::Medium.larger(), CaptionSize::Large);
+        assert_eq!(CaptionSize::Off.larger(), CaptionSize::Small);
+    }
+
+    #[test]
+    fn caption_size_stays_at_the_ends() {
+        assert_eq!(CaptionSize::Off.smaller(), CaptionSize::Off);
+        assert_eq!(CaptionSize::Large.larger(), CaptionSize::Large);
+    }
+}
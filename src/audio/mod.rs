@@ -1,10 +1,12 @@
 use bevy::prelude::*;
 use bevy_seedling::prelude::*;
 
+pub(crate) mod caption;
 pub(crate) mod perceptual;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(Startup, initialize_audio);
+    app.add_plugins(caption::plugin);
 }
 
 #[derive(PoolLabel, Reflect, PartialEq, Eq, Debug, Hash, Clone)]
@@ -0,0 +1,129 @@
+//! Bakes the level's navmesh from its static colliders and writes it into the level's `.nav` file.
+//!
+//! The level is spawned in a [`map_loading_app`] together with the props and physics, which is everything that adds
+//! colliders to it. Next to the `.nav`, we write a hash of the `.map` it was baked from, so the game can tell when the
+//! navmesh is stale.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use avian_rerecast::prelude::*;
+use avian3d::prelude::*;
+use bevy::{
+    animation::AnimationPlugin, ecs::system::RunSystemOnce as _, prelude::*,
+    scene::SceneInstanceReady,
+};
+use bevy_hanabi::prelude::EffectAsset;
+use bevy_rerecast::prelude::*;
+use bevy_seedling::sample::AudioSample;
+
+use super::{map_loading_app, update_until};
+use crate::{
+    gameplay::{
+        level::{LEVEL_MAP_PATH, LEVEL_NAVMESH_PATH, NavmeshColliders, navmesh_settings},
+        npc::NPC_RADIUS,
+    },
+    graphics::GraphicsSettings,
+    props::manifest::PropManifestAssets,
+    stable_hash::hash_bytes,
+};
+
+pub(super) fn bake_navmesh() -> AppExit {
+    match bake_level_navmesh() {
+        Ok(path) => {
+            println!("Wrote the baked navmesh to \"{}\"", path.display());
+            AppExit::Success
+        }
+        Err(error) => {
+            eprintln!("Failed to bake the navmesh: {error}");
+            AppExit::error()
+        }
+    }
+}
+
+/// The file next to a `.nav` that contains the hash of the `.map` it was baked from.
+pub(crate) fn map_hash_path(navmesh_path: &Path) -> PathBuf {
+    navmesh_path.with_extension("nav.map_hash")
+}
+
+/// Starts baking the level's navmesh from the level geometry.
+pub(crate) fn generate_navmesh(
+    mut generator: NavmeshGenerator,
+    colliders: NavmeshColliders,
+) -> Handle<Navmesh> {
+    info!("Baking the navmesh with an agent radius of {NPC_RADIUS}");
+    generator.generate(navmesh_settings(colliders.collect()))
+}
+
+/// Inserted once the level's scene was spawned, at which point its props have started to set up their colliders.
+#[derive(Resource)]
+struct LevelSpawned;
+
+fn bake_level_navmesh() -> Result<PathBuf> {
+    let mut app = navmesh_baking_app();
+    // Generic props can only be set up once the prop manifest is loaded.
+    update_until(&mut app, "the prop manifest", |world| {
+        world.contains_resource::<PropManifestAssets>()
+    })?;
+
+    let level = app
+        .world()
+        .resource::<AssetServer>()
+        .load(format!("{LEVEL_MAP_PATH}#Scene"));
+    app.world_mut().spawn(SceneRoot(level)).observe(
+        |_ready: On<SceneInstanceReady>, mut commands: Commands| {
+            commands.insert_resource(LevelSpawned);
+        },
+    );
+    update_until(&mut app, "the level's colliders", |world| {
+        let mut constructors = world.query_filtered::<(), Or<(
+            With<ColliderConstructor>,
+            With<ColliderConstructorHierarchy>,
+        )>>();
+        world.contains_resource::<LevelSpawned>() && constructors.iter(world).next().is_none()
+    })?;
+
+    let navmesh = app.world_mut().run_system_once(generate_navmesh)?;
+    update_until(&mut app, "the navmesh to bake", |world| {
+        world.resource::<Assets<Navmesh>>().contains(&navmesh)
+    })?;
+    let navmesh = app
+        .world_mut()
+        .resource_mut::<Assets<Navmesh>>()
+        .remove(&navmesh)
+        .ok_or("The baked navmesh is not available")?;
+
+    let assets = Path::new("assets");
+    let map = fs::read(assets.join(LEVEL_MAP_PATH))?;
+    let navmesh_path = assets.join(LEVEL_NAVMESH_PATH);
+    let bytes = bincode::serde::encode_to_vec(&navmesh, bincode::config::standard())?;
+    fs::write(&navmesh_path, bytes)?;
+    fs::write(
+        map_hash_path(&navmesh_path),
+        format!("{:016x}\n", hash_bytes(&map)),
+    )?;
+    Ok(navmesh_path)
+}
+
+/// A [`map_loading_app`] that also sets up the props and physics.
+fn navmesh_baking_app() -> App {
+    let mut app = map_loading_app();
+    app.add_plugins((
+        crate::gltf_plugin(),
+        AnimationPlugin,
+        PhysicsPlugins::default(),
+        NavmeshPlugins::default(),
+        AvianBackendPlugin::default(),
+        crate::asset_tracking::plugin,
+        crate::props::plugin,
+    ));
+    // The props also add sounds and particles, which don't matter for the navmesh but need these to exist.
+    app.init_asset::<AudioSample>();
+    app.init_asset::<EffectAsset>();
+    app.init_resource::<GraphicsSettings>();
+    app.finish();
+    app.cleanup();
+    app
+}
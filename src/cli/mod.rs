@@ -0,0 +1,349 @@
+//! Command line tools that run headless and exit instead of starting the game.
+//!
+//! - `--export-trenchbroom-config <dir>`: writes the TrenchBroom game config, FGD and texture settings
+//!   for all registered classes into `dir`.
+//! - `--validate-maps`: loads all maps under `assets/maps` like the game does, checks them and exits with an error
+//!   if any problems were found.
+//! - `--bake-navmesh`: bakes the navmesh of the level and writes it into the level's `.nav` file.
+
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use bevy::{
+    asset::{AssetMetaCheck, io::AssetSourceId},
+    mesh::MeshPlugin,
+    prelude::*,
+    reflect::TypeRegistry,
+    scene::ScenePlugin,
+};
+use bevy_trenchbroom::prelude::*;
+use bevy_trenchbroom_avian::AvianPhysicsBackend;
+
+use crate::{
+    asset_processing::default_image_sampler_descriptor,
+    props::manifest::{GenericProp, PROP_MANIFEST_PATH, PropManifest, prop_class_asset_source},
+    third_party::bevy_trenchbroom::{TEXTURE_EXCLUSIONS, TEXTURE_EXTENSIONS, trenchbroom_config},
+};
+
+pub(crate) mod bake_navmesh;
+mod validate_maps;
+
+/// Runs the tool requested on the command line, if any. Returns `None` if the game should start normally.
+pub(crate) fn run_command() -> Option<AppExit> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (index, command) = args.iter().enumerate().find(|(_, arg)| is_command(arg))?;
+    let exit = match command.as_str() {
+        "--export-trenchbroom-config" => match args.get(index + 1) {
+            Some(dir) => export_trenchbroom_config(Path::new(dir)),
+            None => {
+                eprintln!("Usage: --export-trenchbroom-config <dir>");
+                AppExit::error()
+            }
+        },
+        "--validate-maps" => validate_maps::validate_maps(),
+        "--bake-navmesh" => bake_navmesh::bake_navmesh(),
+        _ => unreachable!(),
+    };
+    Some(exit)
+}
+
+fn is_command(arg: &str) -> bool {
+    matches!(
+        arg,
+        "--export-trenchbroom-config" | "--validate-maps" | "--bake-navmesh"
+    )
+}
+
+/// An app that knows about all of the game's types, without any plugins. No window is opened and no assets are loaded.
+/// In dev builds, `--check-trenchbroom-config` checks that this registers the same TrenchBroom classes as the game.
+fn headless_app() -> App {
+    // With the `reflect_auto_register` feature, creating the app registers every reflected type,
+    // including all TrenchBroom classes.
+    App::new()
+}
+
+/// A [`headless_app`] that loads maps with the same loader and config as the game.
+/// It has the asset server and what the map loader needs to build the brushes, but still no window, renderer or audio.
+fn map_loading_app() -> App {
+    let mut app = headless_app();
+    app.register_asset_source(AssetSourceId::Default, prop_class_asset_source());
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin {
+            meta_check: AssetMetaCheck::Never,
+            ..default()
+        },
+        TransformPlugin,
+        ImagePlugin {
+            default_sampler: default_image_sampler_descriptor(),
+        },
+        MeshPlugin,
+        ScenePlugin,
+    ));
+    // The map loader creates the materials of the brushes. Without a renderer, nobody else registers them.
+    app.init_asset::<StandardMaterial>();
+    app.add_plugins((
+        TrenchBroomPlugins(trenchbroom_config())
+            .build()
+            .disable::<bevy_trenchbroom::config::ConfigPlugin>(),
+        TrenchBroomPhysicsPlugin::new(AvianPhysicsBackend),
+    ));
+    app
+}
+
+/// How long a tool waits for assets to load or a navmesh to bake before giving up.
+const TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Updates the app until `done` returns true, or fails after [`TIMEOUT`].
+fn update_until(
+    app: &mut App,
+    waiting_for: &str,
+    mut done: impl FnMut(&mut World) -> bool,
+) -> Result {
+    let start = Instant::now();
+    while !done(app.world_mut()) {
+        if start.elapsed() > TIMEOUT {
+            return Err(format!("Timed out waiting for {waiting_for}").into());
+        }
+        app.update();
+        // Assets are loaded and navmeshes baked on other threads, don't spin while waiting for them.
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    Ok(())
+}
+
+/// Reads and validates `assets/generic.props.ron` from disk.
+fn read_prop_manifest() -> Result<PropManifest, String> {
+    let path = Path::new("assets").join(PROP_MANIFEST_PATH);
+    let manifest = std::fs::read_to_string(&path)
+        .map_err(|error| format!("Failed to read \"{}\": {error}", path.display()))?;
+    PropManifest::from_ron(&manifest)
+        .map_err(|error| format!("\"{}\" is invalid: {error}", path.display()))
+}
+
+fn export_trenchbroom_config(dir: &Path) -> AppExit {
+    let texture_maps = listed_texture_maps(&Path::new("assets").join("textures"));
+    if !texture_maps.is_empty() {
+        eprintln!(
+            "TrenchBroom would list these texture maps as textures of their own, add a pattern matching them to \
+            `TEXTURE_EXCLUSIONS`:"
+        );
+        for path in texture_maps {
+            eprintln!("    {}", path.display());
+        }
+        return AppExit::error();
+    }
+
+    let app = headless_app();
+    let registry = app.world().resource::<AppTypeRegistry>().read();
+    match write_trenchbroom_config(dir, &registry) {
+        Ok(()) => {
+            println!("Wrote the TrenchBroom game config to \"{}\"", dir.display());
+            AppExit::Success
+        }
+        Err(error) => {
+            eprintln!(
+                "Failed to write the TrenchBroom game config to \"{}\": {error}",
+                dir.display()
+            );
+            AppExit::error()
+        }
+    }
+}
+
+fn write_trenchbroom_config(dir: &Path, registry: &TypeRegistry) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+    trenchbroom_config()
+        .write_game_config(dir, registry)
+        .map_err(|error| error.to_string())?;
+    add_prop_classes_to_fgd(dir)
+}
+
+/// Writes the config both from `game_registry` and from the [`headless_app`] that `--export-trenchbroom-config` uses,
+/// and returns the names of the files that differ.
+#[cfg(feature = "dev_native")]
+pub(crate) fn exported_config_differences(
+    game_registry: &TypeRegistry,
+) -> Result<Vec<String>, String> {
+    let root = std::env::temp_dir().join(format!("jam-trenchbroom-config-{}", std::process::id()));
+    let game_dir = root.join("game");
+    let exported_dir = root.join("exported");
+    let result = write_trenchbroom_config(&game_dir, game_registry)
+        .and_then(|()| {
+            let app = headless_app();
+            let registry = app.world().resource::<AppTypeRegistry>().read();
+            write_trenchbroom_config(&exported_dir, &registry)
+        })
+        .and_then(|()| {
+            let mut differences = Vec::new();
+            for entry in std::fs::read_dir(&game_dir).map_err(|error| error.to_string())? {
+                let name = entry.map_err(|error| error.to_string())?.file_name();
+                let game = std::fs::read(game_dir.join(&name)).ok();
+                let exported = std::fs::read(exported_dir.join(&name)).ok();
+                if game != exported {
+                    differences.push(name.to_string_lossy().into_owned());
+                }
+            }
+            Ok(differences)
+        });
+    // Best effort, the files are in the temporary directory anyway.
+    let _ = std::fs::remove_dir_all(&root);
+    result
+}
+
+/// Returns the normal, roughness and similar maps under `dir` that would show up in TrenchBroom's texture browser.
+/// These live in a directory named after the base color texture they belong to, e.g. `brick/brick_normal.png` next
+/// to `brick.png`.
+fn listed_texture_maps(dir: &Path) -> Vec<PathBuf> {
+    let mut maps = Vec::new();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return maps;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            let is_map_directory = TEXTURE_EXTENSIONS
+                .iter()
+                .any(|extension| path.with_extension(extension).is_file());
+            if is_map_directory {
+                maps.extend(
+                    std::fs::read_dir(&path)
+                        .into_iter()
+                        .flatten()
+                        .flatten()
+                        .map(|entry| entry.path())
+                        .filter(|path| is_listed_texture(path)),
+                );
+            } else {
+                maps.extend(listed_texture_maps(&path));
+            }
+        }
+    }
+    maps.sort();
+    maps
+}
+
+/// Whether TrenchBroom lists the file as a texture, given the texture extensions and exclusions.
+fn is_listed_texture(path: &Path) -> bool {
+    let has_texture_extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| TEXTURE_EXTENSIONS.contains(&extension));
+    let Some(name) = path.file_stem().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    has_texture_extension
+        && !TEXTURE_EXCLUSIONS
+            .iter()
+            .any(|pattern| matches_wildcard(pattern, name))
+}
+
+/// Matches `text` against a pattern where `*` stands for any number of characters.
+fn matches_wildcard(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((prefix, rest)) => {
+            let Some(text) = text.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=text.len())
+                .filter(|index| text.is_char_boundary(*index))
+                .any(|index| matches_wildcard(rest, &text[index..]))
+        }
+    }
+}
+
+/// The classes of the prop manifest are read at runtime, so they are added to the FGD after it was written. Each gets
+/// the bases of [`GenericProp`], which itself is turned into a base class so that it isn't placed by hand.
+fn add_prop_classes_to_fgd(dir: &Path) -> Result<(), String> {
+    let manifest = read_prop_manifest()?;
+    let class_definition = format!("= {}", GenericProp::CLASS_INFO.name);
+
+    let entries = std::fs::read_dir(dir).map_err(|error| error.to_string())?;
+    for entry in entries {
+        let path = entry.map_err(|error| error.to_string())?.path();
+        if path.extension().is_none_or(|extension| extension != "fgd") {
+            continue;
+        }
+        let fgd = std::fs::read_to_string(&path).map_err(|error| error.to_string())?;
+        let mut lines = Vec::new();
+        let mut prop_classes = Vec::new();
+        for line in fgd.lines() {
+            let Some(index) = line
+                .find(&class_definition)
+                .filter(|_| line.starts_with("@PointClass"))
+            else {
+                lines.push(line.to_string());
+                continue;
+            };
+            let bases = line[..index].trim_start_matches("@PointClass");
+            prop_classes.extend(manifest.iter().map(|prop| {
+                format!(
+                    "@PointClass{bases}model(\"{}\") = {} : \"{:?} prop from {PROP_MANIFEST_PATH}\" []",
+                    prop.model, prop.classname, prop.physics,
+                )
+            }));
+            lines.push(line.replacen("@PointClass", "@BaseClass", 1));
+        }
+        lines.extend(prop_classes);
+        std::fs::write(&path, lines.join("\n") + "\n").map_err(|error| error.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcards_match_any_characters() {
+        assert!(matches_wildcard("*_normal", "brick_normal"));
+        assert!(matches_wildcard("*_arm_*", "brick_arm_2k"));
+        assert!(matches_wildcard("brick", "brick"));
+        assert!(!matches_wildcard("*_normal", "brick_normal_old"));
+        assert!(!matches_wildcard("*_arm_*", "brick_armor"));
+    }
+
+    #[test]
+    fn texture_maps_are_not_listed() {
+        assert!(!is_listed_texture(Path::new("brick/brick_normal.png")));
+        assert!(!is_listed_texture(Path::new("brick/brick_roughness.png")));
+        assert!(is_listed_texture(Path::new("brick.png")));
+        assert!(!is_listed_texture(Path::new("brick.toml")));
+    }
+
+    #[test]
+    fn shipped_textures_have_no_listed_texture_maps() {
+        assert_eq!(
+            listed_texture_maps(&Path::new("assets").join("textures")),
+            Vec::<PathBuf>::new()
+        );
+    }
+
+    #[test]
+    fn exported_fgd_has_a_class_per_manifest_prop() {
+        let dir = std::env::temp_dir().join(format!("jam-export-test-{}", std::process::id()));
+        let app = headless_app();
+        let registry = app.world().resource::<AppTypeRegistry>().read();
+        write_trenchbroom_config(&dir, &registry).unwrap();
+        let fgd = std::fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.path())
+            .find(|path| path.extension().is_some_and(|extension| extension == "fgd"))
+            .map(|path| std::fs::read_to_string(path).unwrap())
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        let line = fgd
+            .lines()
+            .find(|line| line.contains("= barrel_large_closed "))
+            .unwrap();
+        assert!(line.starts_with("@PointClass"));
+        assert!(line.contains("model(\"models/darkmod/containers/barrel_large_closed.gltf\")"));
+        assert!(
+            fgd.lines()
+                .any(|line| line.starts_with("@BaseClass") && line.contains("= generic_prop"))
+        );
+    }
+}
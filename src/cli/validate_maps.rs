@@ -0,0 +1,439 @@
+//! Checks every `.map` under `assets/maps` for mistakes that would otherwise only show up at runtime,
+//! or not at all.
+//!
+//! The maps are loaded with the game's map loader, so a property that can't be deserialized into its class is
+//! reported exactly like the game would fail on it. The rest, like missing textures or Yarn nodes, is checked on the
+//! `.map` file itself.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Display},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    asset::{AssetPath, LoadState},
+    prelude::*,
+    reflect::{TypeRegistration, TypeRegistry},
+};
+use bevy_trenchbroom::class::{QuakeClass as _, ReflectQuakeClass};
+
+use super::{TIMEOUT, map_loading_app, read_prop_manifest, update_until};
+use crate::{gameplay::player::Player, props::manifest::PropManifest};
+
+const ASSETS_DIR: &str = "assets";
+const MAPS_DIR: &str = "assets/maps";
+const TEXTURES_DIR: &str = "assets/textures";
+/// Files that can back a texture referenced by a brush face. The `.toml` files are material definitions.
+const TEXTURE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "toml"];
+/// Classes that TrenchBroom and `bevy_trenchbroom` handle themselves.
+const BUILTIN_CLASSNAMES: &[&str] = &["worldspawn", "func_group"];
+
+pub(super) fn validate_maps() -> AppExit {
+    let yarn_nodes = match collect_yarn_nodes(Path::new(ASSETS_DIR)) {
+        Ok(nodes) => nodes,
+        Err(error) => {
+            eprintln!("Failed to read the Yarn files: {error}");
+            return AppExit::error();
+        }
+    };
+    let props = match read_prop_manifest() {
+        Ok(props) => props,
+        Err(error) => {
+            eprintln!("{error}");
+            return AppExit::error();
+        }
+    };
+    let mut map_paths = Vec::new();
+    if let Err(error) = collect_files(Path::new(MAPS_DIR), "map", &mut map_paths) {
+        eprintln!("Failed to read {MAPS_DIR}: {error}");
+        return AppExit::error();
+    }
+    map_paths.sort();
+
+    let mut app = map_loading_app();
+    app.finish();
+    app.cleanup();
+    let load_errors = load_maps(&mut app, &map_paths);
+    let registry = app.world().resource::<AppTypeRegistry>().read();
+    let validator = Validator::new(&registry, yarn_nodes, props);
+    let mut problem_count = 0;
+    for path in &map_paths {
+        let mut problems = match fs::read_to_string(path) {
+            Ok(map) => validator.validate(&parse_map(&map)),
+            Err(error) => vec![Problem::new(0, format!("failed to read the map: {error}"))],
+        };
+        if let Some(error) = load_errors.get(path) {
+            problems.insert(
+                0,
+                Problem::new(0, format!("failed to load the map: {error}")),
+            );
+        }
+        for problem in &problems {
+            eprintln!("{}:{problem}", path.display());
+        }
+        problem_count += problems.len();
+    }
+
+    if problem_count == 0 {
+        println!("Validated {} maps, no problems found", map_paths.len());
+        AppExit::Success
+    } else {
+        eprintln!("Found {problem_count} problems in {} maps", map_paths.len());
+        AppExit::error()
+    }
+}
+
+/// Loads the scenes of all maps and returns the error of every map that failed to load or didn't load within
+/// [`TIMEOUT`].
+/// Spawning the scene deserializes each entity's properties into its class, so this catches e.g. a `must_set`
+/// property that is missing or a value that doesn't parse into its field.
+fn load_maps(app: &mut App, map_paths: &[PathBuf]) -> HashMap<PathBuf, String> {
+    let asset_server = app.world().resource::<AssetServer>().clone();
+    let mut loading: Vec<(&PathBuf, Handle<Scene>)> = map_paths
+        .iter()
+        .map(|path| {
+            let asset_path = path.strip_prefix(ASSETS_DIR).unwrap_or(path);
+            let asset_path = AssetPath::from_path(asset_path)
+                .with_label("Scene")
+                .into_owned();
+            (path, asset_server.load(asset_path))
+        })
+        .collect();
+
+    let mut errors = HashMap::new();
+    let loaded = update_until(app, "the maps to load", |_world| {
+        loading.retain(|(path, handle)| match asset_server.load_state(handle) {
+            LoadState::Loaded => false,
+            LoadState::Failed(error) => {
+                errors.insert((*path).clone(), error.to_string());
+                false
+            }
+            LoadState::NotLoaded | LoadState::Loading => true,
+        });
+        loading.is_empty()
+    });
+    if loaded.is_err() {
+        for (path, _) in loading {
+            errors.insert(
+                path.clone(),
+                format!("timed out after {} seconds", TIMEOUT.as_secs()),
+            );
+        }
+    }
+    errors
+}
+
+struct Problem {
+    line: usize,
+    message: String,
+}
+
+impl Problem {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: error: {}", self.line, self.message)
+    }
+}
+
+/// An entity of a `.map` file. Only the parts that are validated are kept.
+#[derive(Default)]
+struct MapEntity {
+    /// The line of the entity's opening brace.
+    line: usize,
+    /// Key, value and line of every property.
+    properties: Vec<(String, String, usize)>,
+    /// Texture name and line of every brush face.
+    textures: Vec<(String, usize)>,
+}
+
+impl MapEntity {
+    fn property(&self, key: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(property, _, _)| property == key)
+            .map(|(_, value, _)| value.as_str())
+    }
+}
+
+fn parse_map(map: &str) -> Vec<MapEntity> {
+    let mut entities = Vec::new();
+    let mut current = None;
+    let mut depth = 0;
+    for (index, line) in map.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.starts_with("//") {
+            continue;
+        }
+        match line {
+            "{" => {
+                depth += 1;
+                if depth == 1 {
+                    current = Some(MapEntity {
+                        line: line_number,
+                        ..default()
+                    });
+                }
+            }
+            "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    entities.extend(current.take());
+                }
+            }
+            _ => {
+                let Some(entity) = current.as_mut() else {
+                    continue;
+                };
+                if depth == 1 {
+                    if let Some((key, value)) = parse_property(line) {
+                        entity.properties.push((key, value, line_number));
+                    }
+                } else if let Some(texture) = parse_face_texture(line) {
+                    entity.textures.push((texture.to_string(), line_number));
+                }
+            }
+        }
+    }
+    entities
+}
+
+/// Parses `"key" "value"`.
+fn parse_property(line: &str) -> Option<(String, String)> {
+    let mut parts = line.split('"');
+    // The text before the first quote.
+    parts.next()?;
+    let key = parts.next()?;
+    parts.next()?;
+    let value = parts.next()?;
+    Some((key.to_string(), value.to_string()))
+}
+
+/// Returns the texture of a brush face like `( x y z ) ( x y z ) ( x y z ) texture [ ... ] ...`.
+fn parse_face_texture(line: &str) -> Option<&str> {
+    if !line.starts_with('(') {
+        return None;
+    }
+    let after_points = line.match_indices(')').nth(2)?.0 + 1;
+    line[after_points..].split_whitespace().next()
+}
+
+struct Validator<'a> {
+    /// All registered classes by classname.
+    classes: HashMap<&'static str, &'a TypeRegistration>,
+    yarn_nodes: HashSet<String>,
+    props: PropManifest,
+}
+
+impl<'a> Validator<'a> {
+    fn new(registry: &'a TypeRegistry, yarn_nodes: HashSet<String>, props: PropManifest) -> Self {
+        let classes = registry
+            .iter_with_data::<ReflectQuakeClass>()
+            .map(|(registration, class)| (class.erased_class.info.name, registration))
+            .collect();
+        Self {
+            classes,
+            yarn_nodes,
+            props,
+        }
+    }
+
+    fn validate(&self, entities: &[MapEntity]) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        let mut checked_models = HashSet::new();
+        let mut checked_textures = HashSet::new();
+        let mut player_lines = Vec::new();
+
+        for entity in entities {
+            let Some(classname) = entity.property("classname") else {
+                problems.push(Problem::new(entity.line, "entity has no classname"));
+                continue;
+            };
+            if classname == Player::CLASS_INFO.name {
+                player_lines.push(entity.line);
+            }
+            for (texture, line) in &entity.textures {
+                // TrenchBroom's special textures like `__TB_empty` have no file.
+                if texture.starts_with("__") || !checked_textures.insert(texture.as_str()) {
+                    continue;
+                }
+                let exists = TEXTURE_EXTENSIONS.iter().any(|extension| {
+                    Path::new(TEXTURES_DIR)
+                        .join(format!("{texture}.{extension}"))
+                        .exists()
+                });
+                if !exists {
+                    problems.push(Problem::new(
+                        *line,
+                        format!("texture \"{texture}\" does not exist"),
+                    ));
+                }
+            }
+            if BUILTIN_CLASSNAMES.contains(&classname) {
+                continue;
+            }
+            let model_path = if let Some(prop) = self.props.get(classname) {
+                Some(prop.model.as_str())
+            } else if let Some(registration) = self.classes.get(classname) {
+                registration
+                    .data::<ReflectQuakeClass>()
+                    .and_then(|class| class.erased_class.info.model_path())
+            } else {
+                problems.push(Problem::new(
+                    entity.line,
+                    format!("classname \"{classname}\" is not registered"),
+                ));
+                continue;
+            };
+            if let Some(model_path) = model_path {
+                if checked_models.insert(model_path)
+                    && !Path::new(ASSETS_DIR).join(model_path).exists()
+                {
+                    problems.push(Problem::new(
+                        entity.line,
+                        format!("model \"{model_path}\" of class \"{classname}\" does not exist"),
+                    ));
+                }
+            }
+
+            for (key, value, line) in &entity.properties {
+                if key == "yarn_node" && !self.yarn_nodes.contains(value) {
+                    problems.push(Problem::new(
+                        *line,
+                        format!("no Yarn node named \"{value}\" exists"),
+                    ));
+                }
+            }
+        }
+
+        if player_lines.len() > 1 {
+            for line in player_lines {
+                problems.push(Problem::new(
+                    line,
+                    "there is more than one player, but the game expects exactly one",
+                ));
+            }
+        }
+        problems.sort_by_key(|problem| problem.line);
+        problems
+    }
+}
+
+/// Collects the titles of all nodes in all `.yarn` files under `dir`.
+fn collect_yarn_nodes(dir: &Path) -> std::io::Result<HashSet<String>> {
+    let mut yarn_paths = Vec::new();
+    collect_files(dir, "yarn", &mut yarn_paths)?;
+    let mut nodes = HashSet::new();
+    for path in yarn_paths {
+        let yarn = fs::read_to_string(path)?;
+        nodes.extend(
+            yarn.lines()
+                .filter_map(|line| line.trim().strip_prefix("title:"))
+                .map(|title| title.trim().to_string()),
+        );
+    }
+    Ok(nodes)
+}
+
+fn collect_files(dir: &Path, extension: &str, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, extension, files)?;
+        } else if path.extension().is_some_and(|ext| ext == extension) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = r#"// Game: jam
+// Format: Valve
+// entity 0
+{
+"classname" "worldspawn"
+"_tb_textures" "textures"
+// brush 0
+{
+( -64 -64 -16 ) ( -64 -63 -16 ) ( -64 -64 -15 ) wood [ 0 -1 0 0 ] [ 0 0 -1 0 ] 0 1 1
+( 64 64 16 ) ( 64 64 17 ) ( 64 65 16 ) __TB_empty [ 0 1 0 0 ] [ 0 0 -1 0 ] 0 1 1
+}
+}
+// entity 1
+{
+"classname" "barrel_large_closed"
+"origin" "0 -8 24"
+"angles" "0 90 0"
+}
+"#;
+
+    #[test]
+    fn parses_entities_with_their_lines() {
+        let entities = parse_map(MAP);
+        assert_eq!(entities.len(), 2);
+        assert_eq!(entities[0].line, 4);
+        assert_eq!(entities[0].property("classname"), Some("worldspawn"));
+        assert_eq!(
+            entities[0].textures,
+            vec![("wood".to_string(), 9), ("__TB_empty".to_string(), 10)]
+        );
+        assert_eq!(entities[1].line, 14);
+        assert_eq!(entities[1].property("origin"), Some("0 -8 24"));
+        assert_eq!(
+            entities[1].properties[2],
+            ("angles".to_string(), "0 90 0".to_string(), 17)
+        );
+        assert!(entities[1].textures.is_empty());
+    }
+
+    #[test]
+    fn skips_comments_and_text_outside_of_entities() {
+        let entities = parse_map("// \"classname\" \"ignored\"\n\"stray\" \"value\"\n{\n}\n");
+        assert_eq!(entities.len(), 1);
+        assert!(entities[0].properties.is_empty());
+    }
+
+    #[test]
+    fn parses_properties() {
+        assert_eq!(
+            parse_property(r#""message" "Hello there""#),
+            Some(("message".to_string(), "Hello there".to_string()))
+        );
+        assert_eq!(
+            parse_property(r#""target" """#),
+            Some(("target".to_string(), String::new()))
+        );
+        assert_eq!(parse_property(r#""key""#), None);
+        assert_eq!(parse_property("key value"), None);
+    }
+
+    #[test]
+    fn parses_face_textures() {
+        assert_eq!(
+            parse_face_texture(
+                "( 0 0 0 ) ( 1 0 0 ) ( 0 1 0 ) darkmod/wall [ 1 0 0 0 ] [ 0 -1 0 0 ] 0 1 1"
+            ),
+            Some("darkmod/wall")
+        );
+        assert_eq!(
+            parse_face_texture("( 0 0 0 ) ( 1 0 0 ) ( 0 1 0 ) wood 0 0 0 1 1"),
+            Some("wood")
+        );
+        assert_eq!(parse_face_texture("( 0 0 0 ) ( 1 0 0 )"), None);
+        assert_eq!(parse_face_texture(r#""classname" "light""#), None);
+    }
+}
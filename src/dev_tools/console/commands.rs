@@ -0,0 +1,226 @@
+//! The built-in console commands.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_yarnspinner::prelude::*;
+
+use super::{Console, ConsoleCommands, RegisterConsoleCommand as _, cvars};
+use crate::{
+    dev_tools::{
+        noclip::toggle_noclip,
+        spawn_menu::{
+            clear_dev_spawned, crosshair_spawn_transform, facing_camera, spawn_dev_class,
+        },
+    },
+    gameplay::{
+        level::LevelAssets,
+        player::{Player, camera::PlayerCamera, dialogue::start_dialogue},
+    },
+    props::manifest::LoadedPropManifest,
+    screens::Screen,
+    third_party::bevy_trenchbroom::point_class_names,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_console_command("help", "help: list all commands", help)
+        .register_console_command("clear", "clear: clear the console", clear)
+        .register_console_command(
+            "cvars",
+            "cvars [filter]: list all cvars, optionally only those containing `filter`",
+            list_cvars,
+        )
+        .register_console_command(
+            "teleport",
+            "teleport <x> <y> <z>: move the player to the given position",
+            teleport,
+        )
+        .register_console_command("noclip", "noclip: toggle the free-flying camera", noclip)
+        .register_console_command(
+            "classes",
+            "classes: list all point classes that can be spawned",
+            list_classes,
+        )
+        .register_console_command(
+            "give",
+            "give <class>: spawn a point class right in front of the player",
+            give,
+        )
+        .register_console_command(
+            "spawn",
+            "spawn <class>: spawn a point class where the crosshair is pointing",
+            spawn,
+        )
+        .register_console_command(
+            "clear_spawned",
+            "clear_spawned: despawn everything spawned with `give`, `spawn` or the spawn menu",
+            clear_spawned,
+        )
+        .register_console_command(
+            "start_dialogue",
+            "start_dialogue <node>: start the given Yarn node",
+            start_dialogue_command,
+        )
+        .register_console_command(
+            "timescale",
+            "timescale [scale]: print or set the speed at which game time passes",
+            timescale,
+        )
+        .register_console_command(
+            "reload_map",
+            "reload_map: reload the level from disk and respawn it",
+            reload_map,
+        );
+}
+
+fn help(_args: In<Vec<String>>, commands: Res<ConsoleCommands>) -> Result<String> {
+    let help = commands
+        .values()
+        .map(|command| command.help)
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(format!(
+        "{help}\n<cvar> [value]: print or set a reflected resource, e.g. `WorldModelFov 90`"
+    ))
+}
+
+fn clear(_args: In<Vec<String>>, mut console: ResMut<Console>) -> Result<String> {
+    console.log.clear();
+    Ok(String::new())
+}
+
+fn list_cvars(In(args): In<Vec<String>>, type_registry: Res<AppTypeRegistry>) -> Result<String> {
+    let filter = args.first().map(|filter| filter.to_lowercase());
+    let mut names: Vec<_> = cvars::cvar_names(&type_registry.read())
+        .filter(|name| {
+            filter
+                .as_ref()
+                .is_none_or(|filter| name.to_lowercase().contains(filter))
+        })
+        .collect();
+    names.sort_unstable();
+    Ok(names.join("\n"))
+}
+
+fn teleport(
+    In(args): In<Vec<String>>,
+    player: Single<(&mut Transform, &mut LinearVelocity), With<Player>>,
+) -> Result<String> {
+    let [x, y, z] = args.as_slice() else {
+        return Err("Usage: teleport <x> <y> <z>".into());
+    };
+    let translation = Vec3::new(x.parse()?, y.parse()?, z.parse()?);
+    let (mut transform, mut velocity) = player.into_inner();
+    transform.translation = translation;
+    velocity.0 = Vec3::ZERO;
+    Ok(format!("Teleported to {translation}"))
+}
+
+fn noclip(_args: In<Vec<String>>, mut commands: Commands) -> Result<String> {
+    commands.run_system_cached(toggle_noclip);
+    Ok(String::new())
+}
+
+fn list_classes(
+    _args: In<Vec<String>>,
+    type_registry: Res<AppTypeRegistry>,
+    props: LoadedPropManifest,
+) -> Result<String> {
+    Ok(point_class_names(&type_registry.read(), props.get()).join("\n"))
+}
+
+fn give(
+    In(args): In<Vec<String>>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    mut commands: Commands,
+) -> Result<String> {
+    let [classname] = args.as_slice() else {
+        return Err("Usage: give <class>".into());
+    };
+    let camera = camera.compute_transform();
+    const GIVE_DISTANCE: f32 = 1.5;
+    let translation = camera.translation + camera.forward() * GIVE_DISTANCE;
+    let transform = Transform::from_translation(translation).with_rotation(facing_camera(&camera));
+    spawn_class_deferred(&mut commands, classname, transform);
+    Ok(String::new())
+}
+
+fn spawn(
+    In(args): In<Vec<String>>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    player: Single<Entity, With<Player>>,
+    spatial_query: SpatialQuery,
+    mut commands: Commands,
+) -> Result<String> {
+    let [classname] = args.as_slice() else {
+        return Err("Usage: spawn <class>".into());
+    };
+    let transform = crosshair_spawn_transform(&camera, *player, &spatial_query)
+        .ok_or("The crosshair is not pointing at anything")?;
+    spawn_class_deferred(&mut commands, classname, transform);
+    Ok(String::new())
+}
+
+fn spawn_class_deferred(commands: &mut Commands, classname: &str, transform: Transform) {
+    let classname = classname.to_string();
+    commands.queue(move |world: &mut World| {
+        let message = match spawn_dev_class(world, &classname, transform) {
+            Ok(entity) => format!("Spawned {classname} ({entity})"),
+            Err(error) => format!("Error: {error}"),
+        };
+        world.resource_mut::<Console>().print(message);
+    });
+}
+
+fn clear_spawned(_args: In<Vec<String>>, world: &mut World) -> Result<String> {
+    let count = clear_dev_spawned(world);
+    Ok(format!("Cleared {count} spawned entities"))
+}
+
+fn start_dialogue_command(
+    In(args): In<Vec<String>>,
+    dialogue_runner: Single<&DialogueRunner>,
+    mut commands: Commands,
+) -> Result<String> {
+    let [node] = args.as_slice() else {
+        return Err("Usage: start_dialogue <node>".into());
+    };
+    if dialogue_runner.is_running() {
+        return Err("A dialogue is already running".into());
+    }
+    if !dialogue_runner.node_exists(node) {
+        return Err(format!("No Yarn node named \"{node}\" exists").into());
+    }
+    commands.run_system_cached_with(start_dialogue, node.clone());
+    Ok(String::new())
+}
+
+fn timescale(In(args): In<Vec<String>>, mut time: ResMut<Time<Virtual>>) -> Result<String> {
+    if let Some(scale) = args.first() {
+        let scale: f32 = scale.parse()?;
+        if scale < 0.0 {
+            return Err("The timescale cannot be negative".into());
+        }
+        time.set_relative_speed(scale);
+    }
+    Ok(format!("timescale = {}", time.relative_speed()))
+}
+
+fn reload_map(
+    _args: In<Vec<String>>,
+    level_assets: Res<LevelAssets>,
+    asset_server: Res<AssetServer>,
+    screen: Res<State<Screen>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) -> Result<String> {
+    if screen.get() != &Screen::Gameplay {
+        return Err("No map is loaded".into());
+    }
+    let Some(path) = level_assets.level.path() else {
+        return Err("The level was not loaded from a file".into());
+    };
+    // Reload the whole `.map` file, not just its scene label.
+    asset_server.reload(path.without_label().clone_owned());
+    // Going through the loading screen despawns the current level and spawns it again.
+    next_screen.set(Screen::Loading);
+    Ok(format!("Reloading {path}"))
+}
@@ -0,0 +1,119 @@
+//! Cvars are reflected [`Resource`]s that can be read and written from the console by name,
+//! optionally followed by a reflection path to a field, e.g. `CameraSensitivity.x`.
+
+use std::{fmt::Display, str::FromStr};
+
+use bevy::{
+    prelude::*,
+    reflect::{GetPath as _, ReflectMut, TypeRegistration, TypeRegistry},
+};
+
+pub(super) fn cvar_names(registry: &TypeRegistry) -> impl Iterator<Item = String> {
+    registry
+        .iter_with_data::<ReflectResource>()
+        .map(|(registration, _)| cvar_name(registration).to_string())
+}
+
+fn cvar_name(registration: &TypeRegistration) -> &'static str {
+    registration.type_info().type_path_table().short_path()
+}
+
+/// Prints the cvar's value if `args` is empty, otherwise parses `args` into the cvar.
+pub(super) fn get_or_set_cvar(world: &mut World, name: &str, args: &[String]) -> Result<String> {
+    let (resource_name, path) = name.split_at(name.find(['.', '[']).unwrap_or(name.len()));
+    let reflect_resource = {
+        let registry = world.resource::<AppTypeRegistry>().read();
+        registry
+            .iter_with_data::<ReflectResource>()
+            .find(|(registration, _)| cvar_name(registration).eq_ignore_ascii_case(resource_name))
+            .map(|(_, reflect_resource)| reflect_resource.clone())
+            .ok_or_else(|| format!("Unknown command or cvar \"{resource_name}\""))?
+    };
+
+    if args.is_empty() {
+        let resource = reflect_resource.reflect(&*world)?;
+        let value = resource
+            .reflect_path(path)
+            .map_err(|error| error.to_string())?;
+        return Ok(format!("{name} = {value:?}"));
+    }
+
+    let mut resource = reflect_resource.reflect_mut(world)?;
+    let value = resource
+        .reflect_path_mut(path)
+        .map_err(|error| error.to_string())?;
+    set_value(value, args)?;
+    Ok(format!("{name} = {value:?}"))
+}
+
+fn set_value(value: &mut dyn PartialReflect, args: &[String]) -> Result {
+    if let Some(result) = try_set_from_str::<f32>(value, args)
+        .or_else(|| try_set_from_str::<f64>(value, args))
+        .or_else(|| try_set_from_str::<bool>(value, args))
+        .or_else(|| try_set_from_str::<u8>(value, args))
+        .or_else(|| try_set_from_str::<u32>(value, args))
+        .or_else(|| try_set_from_str::<u64>(value, args))
+        .or_else(|| try_set_from_str::<usize>(value, args))
+        .or_else(|| try_set_from_str::<i32>(value, args))
+        .or_else(|| try_set_from_str::<i64>(value, args))
+    {
+        return result;
+    }
+    if let Some(string) = value.try_downcast_mut::<String>() {
+        *string = args.join(" ");
+        return Ok(());
+    }
+    if let Some(vec) = value.try_downcast_mut::<Vec2>() {
+        *vec = match parse_floats(args)?.as_slice() {
+            [splat] => Vec2::splat(*splat),
+            [x, y] => Vec2::new(*x, *y),
+            _ => return Err("Expected 1 or 2 numbers".into()),
+        };
+        return Ok(());
+    }
+    if let Some(vec) = value.try_downcast_mut::<Vec3>() {
+        *vec = match parse_floats(args)?.as_slice() {
+            [splat] => Vec3::splat(*splat),
+            [x, y, z] => Vec3::new(*x, *y, *z),
+            _ => return Err("Expected 1 or 3 numbers".into()),
+        };
+        return Ok(());
+    }
+    // Newtypes like `WorldModelFov(f32)` can be set directly without writing `.0`.
+    if let ReflectMut::TupleStruct(tuple_struct) = value.reflect_mut() {
+        if tuple_struct.field_len() == 1 {
+            return set_value(tuple_struct.field_mut(0).unwrap(), args);
+        }
+    }
+    Err(format!(
+        "Setting values of type `{}` is not supported, try setting one of its fields instead",
+        value.reflect_short_type_path()
+    )
+    .into())
+}
+
+/// Returns `None` if `value` is not a `T`.
+fn try_set_from_str<T>(value: &mut dyn PartialReflect, args: &[String]) -> Option<Result>
+where
+    T: FromStr + 'static,
+    T::Err: Display,
+{
+    let value = value.try_downcast_mut::<T>()?;
+    let result = match args {
+        [arg] => arg
+            .parse::<T>()
+            .map(|parsed| *value = parsed)
+            .map_err(|error| format!("Failed to parse \"{arg}\": {error}").into()),
+        _ => Err("Expected exactly 1 value".into()),
+    };
+    Some(result)
+}
+
+fn parse_floats(args: &[String]) -> Result<Vec<f32>> {
+    args.iter()
+        .map(|arg| {
+            arg.parse::<f32>()
+                .map_err(|error| format!("Failed to parse \"{arg}\": {error}").into())
+        })
+        .collect()
+}
@@ -0,0 +1,316 @@
+//! A drop-down developer console. Press F1 to open it.
+//!
+//! Commands are one-shot systems registered with [`RegisterConsoleCommand::register_console_command`].
+//! Every reflected [`Resource`] doubles as a cvar: typing its name prints its value, typing its name followed
+//! by a value sets it. Fields can be accessed by appending a reflection path, e.g. `CameraSensitivity.x 2.0`.
+
+use std::collections::BTreeMap;
+
+use bevy::{
+    ecs::system::SystemId,
+    input::{ButtonState, InputSystems, keyboard::KeyboardInput},
+    prelude::*,
+};
+use bevy_enhanced_input::prelude::*;
+
+use crate::PostPhysicsAppSystems;
+
+mod commands;
+mod cvars;
+mod ui;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Console>();
+    app.init_resource::<ConsoleCommands>();
+    app.add_plugins((commands::plugin, ui::plugin));
+    app.add_systems(
+        PreUpdate,
+        (toggle_console, edit_console_input.run_if(is_console_open))
+            .chain()
+            .after(InputSystems),
+    );
+    app.add_systems(
+        Update,
+        execute_pending_console_commands
+            .run_if(has_pending_console_commands)
+            .in_set(PostPhysicsAppSystems::Update),
+    );
+    #[cfg(feature = "native")]
+    app.add_systems(
+        OnEnter(crate::screens::Screen::Gameplay),
+        run_startup_script,
+    );
+}
+
+/// The state of the console, including everything that was typed into it.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct Console {
+    pub(crate) open: bool,
+    /// The line currently being typed.
+    pub(crate) input: String,
+    /// Everything the console printed so far, oldest first.
+    pub(crate) log: Vec<String>,
+    /// Previously submitted lines, oldest first.
+    history: Vec<String>,
+    /// The entry of `history` currently shown in `input` while browsing with the arrow keys.
+    history_index: Option<usize>,
+    /// Submitted lines that have not been executed yet.
+    pending: Vec<String>,
+}
+
+impl Console {
+    /// Queues a line to be executed as if it was typed into the console.
+    pub(crate) fn submit(&mut self, line: impl Into<String>) {
+        self.pending.push(line.into());
+    }
+
+    pub(crate) fn print(&mut self, line: impl Into<String>) {
+        self.log.push(line.into());
+    }
+}
+
+/// A command that can be run from the console.
+pub(crate) struct ConsoleCommand {
+    pub(crate) help: &'static str,
+    system: SystemId<In<Vec<String>>, Result<String>>,
+}
+
+/// All registered console commands, sorted by name.
+#[derive(Resource, Default, Deref)]
+pub(crate) struct ConsoleCommands(BTreeMap<&'static str, ConsoleCommand>);
+
+pub(crate) trait RegisterConsoleCommand {
+    /// Registers a one-shot system as a console command. The system receives the whitespace-separated arguments
+    /// typed after the command's name and returns the text to print.
+    fn register_console_command<M>(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        system: impl IntoSystem<In<Vec<String>>, Result<String>, M> + 'static,
+    ) -> &mut Self;
+}
+
+impl RegisterConsoleCommand for App {
+    fn register_console_command<M>(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        system: impl IntoSystem<In<Vec<String>>, Result<String>, M> + 'static,
+    ) -> &mut Self {
+        let system = self.world_mut().register_system(system);
+        self.world_mut()
+            .resource_mut::<ConsoleCommands>()
+            .0
+            .insert(name, ConsoleCommand { help, system });
+        self
+    }
+}
+
+pub(crate) fn is_console_open(console: Res<Console>) -> bool {
+    console.open
+}
+
+fn has_pending_console_commands(console: Res<Console>) -> bool {
+    !console.pending.is_empty()
+}
+
+fn toggle_console(
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut console: ResMut<Console>,
+    mut action_sources: ResMut<ActionSources>,
+) {
+    if !keys.just_pressed(KeyCode::F1) && !(console.open && keys.just_pressed(KeyCode::Escape)) {
+        return;
+    }
+    console.open = !console.open;
+    keys.reset_all();
+    // Typing into the console should not move the player or trigger any other bindings.
+    let sources_enabled = !console.open;
+    action_sources.keyboard = sources_enabled;
+    action_sources.mouse_buttons = sources_enabled;
+    action_sources.mouse_motion = sources_enabled;
+    action_sources.mouse_wheel = sources_enabled;
+}
+
+fn edit_console_input(
+    mut keyboard_input: MessageReader<KeyboardInput>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut console: ResMut<Console>,
+    commands: Res<ConsoleCommands>,
+    type_registry: Res<AppTypeRegistry>,
+) {
+    for input in keyboard_input.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+        match input.key_code {
+            KeyCode::Enter | KeyCode::NumpadEnter => {
+                let line = std::mem::take(&mut console.input);
+                let line = line.trim();
+                console.history_index = None;
+                if line.is_empty() {
+                    continue;
+                }
+                console.print(format!("> {line}"));
+                if console.history.last().is_none_or(|last| last != line) {
+                    console.history.push(line.to_string());
+                }
+                console.submit(line);
+            }
+            KeyCode::Backspace => {
+                console.input.pop();
+            }
+            KeyCode::ArrowUp => {
+                let index = match console.history_index {
+                    Some(index) => index.saturating_sub(1),
+                    None => {
+                        let Some(last) = console.history.len().checked_sub(1) else {
+                            continue;
+                        };
+                        last
+                    }
+                };
+                console.history_index = Some(index);
+                console.input = console.history[index].clone();
+            }
+            KeyCode::ArrowDown => {
+                let Some(index) = console.history_index else {
+                    continue;
+                };
+                if index + 1 < console.history.len() {
+                    console.history_index = Some(index + 1);
+                    console.input = console.history[index + 1].clone();
+                } else {
+                    console.history_index = None;
+                    console.input.clear();
+                }
+            }
+            KeyCode::Tab => {
+                let candidates = completion_candidates(&commands, &type_registry.read());
+                autocomplete(&mut console, &candidates);
+            }
+            // The key that opened the console should not be typed into it.
+            KeyCode::F1 => {}
+            _ => {
+                let Some(text) = &input.text else {
+                    continue;
+                };
+                console
+                    .input
+                    .extend(text.chars().filter(|c| !c.is_control()));
+            }
+        }
+    }
+    // Don't let the game react to anything typed into the console, e.g. pausing when typing a "p".
+    keys.reset_all();
+}
+
+/// All command and cvar names, sorted alphabetically.
+fn completion_candidates(
+    commands: &ConsoleCommands,
+    type_registry: &bevy::reflect::TypeRegistry,
+) -> Vec<String> {
+    let mut candidates: Vec<_> = commands.keys().map(|name| name.to_string()).collect();
+    candidates.extend(cvars::cvar_names(type_registry));
+    candidates.sort_unstable();
+    candidates.dedup();
+    candidates
+}
+
+/// Completes the command or cvar name being typed. If there are multiple matches,
+/// the input is extended to their common prefix and the matches are printed.
+fn autocomplete(console: &mut Console, candidates: &[String]) {
+    if console.input.contains(char::is_whitespace) {
+        return;
+    }
+    let typed = console.input.to_lowercase();
+    let matches: Vec<_> = candidates
+        .iter()
+        .filter(|candidate| candidate.to_lowercase().starts_with(&typed))
+        .collect();
+    match matches.as_slice() {
+        [] => {}
+        [single] => console.input = format!("{single} "),
+        [first, rest @ ..] => {
+            let common_prefix_len = rest.iter().fold(first.len(), |len, candidate| {
+                first
+                    .chars()
+                    .zip(candidate.chars())
+                    .take(len)
+                    .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+                    .count()
+            });
+            console.input = first.chars().take(common_prefix_len).collect();
+            let listing = matches
+                .iter()
+                .map(|candidate| candidate.as_str())
+                .collect::<Vec<_>>()
+                .join("  ");
+            console.print(listing);
+        }
+    }
+}
+
+/// The file whose lines are executed in the console whenever gameplay starts, if it exists.
+#[cfg(feature = "native")]
+const STARTUP_SCRIPT_PATH: &str = "autoexec.cfg";
+
+#[cfg(feature = "native")]
+fn run_startup_script(mut console: ResMut<Console>) {
+    let script = match std::fs::read_to_string(STARTUP_SCRIPT_PATH) {
+        Ok(script) => script,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return,
+        Err(error) => {
+            warn!("Failed to read console startup script \"{STARTUP_SCRIPT_PATH}\": {error}");
+            return;
+        }
+    };
+    info!("Running console startup script \"{STARTUP_SCRIPT_PATH}\"");
+    for line in script.lines() {
+        let line = line.trim();
+        // Lines starting with `//` are comments.
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+        console.submit(line);
+    }
+}
+
+fn execute_pending_console_commands(world: &mut World) {
+    let pending = std::mem::take(&mut world.resource_mut::<Console>().pending);
+    for line in pending {
+        // Multiple commands can be chained with a semicolon.
+        for statement in line.split(';') {
+            let mut words = statement.split_whitespace().map(str::to_string);
+            let Some(name) = words.next() else {
+                continue;
+            };
+            let args: Vec<_> = words.collect();
+            let output = execute_console_command(world, &name, args);
+            let mut console = world.resource_mut::<Console>();
+            match output {
+                Ok(output) => {
+                    if !output.is_empty() {
+                        info!("{output}");
+                        console.print(output);
+                    }
+                }
+                Err(error) => {
+                    warn!("Console command \"{statement}\" failed: {error}");
+                    console.print(format!("Error: {error}"));
+                }
+            }
+        }
+    }
+}
+
+fn execute_console_command(world: &mut World, name: &str, args: Vec<String>) -> Result<String> {
+    let system = world
+        .resource::<ConsoleCommands>()
+        .get(name)
+        .map(|command| command.system);
+    match system {
+        Some(system) => world.run_system_with(system, args)?,
+        None => cvars::get_or_set_cvar(world, name, &args),
+    }
+}
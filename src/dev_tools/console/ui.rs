@@ -0,0 +1,113 @@
+//! The drop-down UI of the console. While it is open, the player's input is blocked and the cursor is freed.
+
+use std::any::Any as _;
+
+use bevy::{prelude::*, ui::Val::*};
+
+use super::Console;
+use crate::{
+    PostPhysicsAppSystems,
+    gameplay::{crosshair::CrosshairState, player::input::BlocksInput},
+    theme::palette::LABEL_TEXT,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, spawn_console_ui);
+    app.add_systems(
+        Update,
+        (update_console_ui, block_input_while_console_is_open)
+            .run_if(resource_changed::<Console>)
+            .in_set(PostPhysicsAppSystems::ChangeUi),
+    );
+}
+
+/// How many lines of the log are shown at once.
+const VISIBLE_LOG_LINES: usize = 20;
+
+#[derive(Component)]
+struct ConsoleUi;
+
+#[derive(Component)]
+struct ConsoleLogText;
+
+#[derive(Component)]
+struct ConsoleInputText;
+
+fn spawn_console_ui(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Console"),
+        ConsoleUi,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Percent(100.0),
+            height: Percent(40.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::End,
+            padding: UiRect::all(Px(8.0)),
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+        GlobalZIndex(10),
+        Visibility::Hidden,
+        children![
+            (
+                Name::new("Console Log"),
+                ConsoleLogText,
+                Text::default(),
+                TextFont::from_font_size(16.0),
+                TextColor(Color::WHITE),
+            ),
+            (
+                Name::new("Console Input"),
+                ConsoleInputText,
+                Text::default(),
+                TextFont::from_font_size(16.0),
+                TextColor(LABEL_TEXT),
+            ),
+        ],
+    ));
+}
+
+fn update_console_ui(
+    console: Res<Console>,
+    mut visibility: Single<&mut Visibility, With<ConsoleUi>>,
+    mut log_text: Single<&mut Text, (With<ConsoleLogText>, Without<ConsoleInputText>)>,
+    mut input_text: Single<&mut Text, (With<ConsoleInputText>, Without<ConsoleLogText>)>,
+) {
+    **visibility = if console.open {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    let lines: Vec<_> = console.log.iter().flat_map(|entry| entry.lines()).collect();
+    let first_visible = lines.len().saturating_sub(VISIBLE_LOG_LINES);
+    log_text.0 = lines[first_visible..].join("\n");
+    input_text.0 = format!("> {}_", console.input);
+}
+
+fn block_input_while_console_is_open(
+    console: Res<Console>,
+    crosshair: Option<Single<&mut CrosshairState>>,
+    mut blocks_input: ResMut<BlocksInput>,
+) {
+    let system_id = block_input_while_console_is_open.type_id();
+    // Typing changes the console too, so avoid triggering change detection on every keystroke.
+    if blocks_input.contains(&system_id) == console.open {
+        return;
+    }
+    if console.open {
+        blocks_input.insert(system_id);
+    } else {
+        blocks_input.remove(&system_id);
+    }
+    // The crosshair only exists during gameplay.
+    let Some(mut crosshair) = crosshair else {
+        return;
+    };
+    if console.open {
+        crosshair.wants_free_cursor.insert(system_id);
+    } else {
+        crosshair.wants_free_cursor.remove(&system_id);
+    }
+}
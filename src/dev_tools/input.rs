@@ -16,6 +16,14 @@ pub(crate) struct ToggleDebugUi;
 #[action_output(bool)]
 pub(crate) struct ForceFreeCursor;
 
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct ToggleNoclip;
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct ToggleSpawnMenu;
+
 #[derive(Debug, Component, Default)]
 struct DevToolsInputContext;
 
@@ -26,6 +34,8 @@ fn setup_dev_tools_input(mut commands: Commands) {
         actions!(DevToolsInputContext[
             (Action::<ToggleDebugUi>::new(), bindings![KeyCode::F3]),
             (Action::<ForceFreeCursor>::new(), bindings![KeyCode::Backquote]),
+            (Action::<ToggleNoclip>::new(), bindings![KeyCode::F4]),
+            (Action::<ToggleSpawnMenu>::new(), bindings![KeyCode::F5]),
         ]),
     ));
 }
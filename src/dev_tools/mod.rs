@@ -2,9 +2,16 @@
 
 use bevy::{dev_tools::states::log_transitions, prelude::*};
 
+mod console;
 mod debug_ui;
 mod input;
 pub(crate) mod log_components;
+#[cfg(feature = "dev_native")]
+mod navmesh_baking;
+mod noclip;
+#[cfg(feature = "dev_native")]
+mod remote;
+pub(crate) mod spawn_menu;
 mod validate_preloading;
 
 use crate::{menus::Menu, screens::loading::LoadingScreen};
@@ -17,9 +24,14 @@ pub(super) fn plugin(app: &mut App) {
     );
 
     app.add_plugins((
+        console::plugin,
         debug_ui::plugin,
         input::plugin,
         validate_preloading::plugin,
         log_components::plugin,
+        noclip::plugin,
+        spawn_menu::plugin,
     ));
+    #[cfg(feature = "dev_native")]
+    app.add_plugins((remote::plugin, navmesh_baking::plugin));
 }
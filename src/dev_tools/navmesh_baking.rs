@@ -0,0 +1,119 @@
+//! Keeps the level's navmesh in sync with the level while working on it.
+//! The shipped `.nav` is only written by `--bake-navmesh`, see [`crate::cli::bake_navmesh`].
+//!
+//! - `--rebake-navmesh`: bakes the navmesh every time the level is loaded while playing normally.
+//!   The new navmesh replaces the loaded one right away, so NPCs use it without restarting the game.
+//!
+//! Independent of this flag, we warn when the shipped `.nav` was baked from a different version of the `.map`,
+//! or for a different agent size than our NPCs have.
+
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy_rerecast::prelude::*;
+
+use crate::{
+    cli::bake_navmesh::{generate_navmesh, map_hash_path},
+    gameplay::{
+        level::LevelAssets,
+        npc::{NPC_HEIGHT, NPC_RADIUS},
+    },
+    screens::Screen,
+    stable_hash::hash_bytes,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), warn_if_navmesh_is_stale);
+
+    if std::env::args().any(|arg| arg == "--rebake-navmesh") {
+        app.add_systems(
+            OnEnter(Screen::Gameplay),
+            generate_navmesh.pipe(track_rebaking_navmesh),
+        );
+        app.add_observer(replace_rebaked_navmesh);
+    }
+}
+
+/// The navmesh that is currently being rebaked.
+#[derive(Resource, Debug)]
+struct RebakingNavmesh(Handle<Navmesh>);
+
+fn track_rebaking_navmesh(In(navmesh): In<Handle<Navmesh>>, mut commands: Commands) {
+    commands.insert_resource(RebakingNavmesh(navmesh));
+}
+
+fn replace_rebaked_navmesh(
+    ready: On<NavmeshReady>,
+    mut commands: Commands,
+    rebaking: Option<Res<RebakingNavmesh>>,
+    level_assets: Res<LevelAssets>,
+    mut navmeshes: ResMut<Assets<Navmesh>>,
+) -> Result {
+    let Some(rebaking) = rebaking else {
+        return Ok(());
+    };
+    if ready.0 != rebaking.0.id() {
+        return Ok(());
+    }
+    commands.remove_resource::<RebakingNavmesh>();
+
+    let navmesh = navmeshes
+        .remove(&rebaking.0)
+        .ok_or("The rebaked navmesh is not available")?;
+    // Replacing the asset in place updates the island that is already using it.
+    navmeshes.insert(&level_assets.navmesh, navmesh)?;
+    info!("Replaced the level's navmesh with the rebaked one");
+    Ok(())
+}
+
+fn warn_if_navmesh_is_stale(
+    level_assets: Res<LevelAssets>,
+    navmeshes: Res<Assets<Navmesh>>,
+    mut warned: Local<bool>,
+) {
+    if *warned {
+        return;
+    }
+    *warned = true;
+
+    let (Some(map_path), Some(navmesh_path)) = (
+        asset_file_path(&level_assets.level),
+        asset_file_path(&level_assets.navmesh),
+    ) else {
+        return;
+    };
+    if let Ok(map) = std::fs::read(&map_path) {
+        let baked_from = std::fs::read_to_string(map_hash_path(&navmesh_path))
+            .ok()
+            .and_then(|hash| u64::from_str_radix(hash.trim(), 16).ok());
+        if baked_from != Some(hash_bytes(&map)) {
+            warn!(
+                "The navmesh {} was not baked from the current version of the map {}. Bake it again with `--bake-navmesh`.",
+                navmesh_path.display(),
+                map_path.display(),
+            );
+        }
+    }
+
+    let Some(navmesh) = navmeshes.get(&level_assets.navmesh) else {
+        return;
+    };
+    let expected = NavmeshSettings::from_agent_3d(NPC_RADIUS, NPC_HEIGHT);
+    if navmesh.settings.agent_radius != expected.agent_radius
+        || navmesh.settings.agent_height != expected.agent_height
+    {
+        warn!(
+            "The navmesh {} was baked for agents with a radius of {} and a height of {}, \
+            but our NPCs have a radius of {NPC_RADIUS} and a height of {NPC_HEIGHT}. Bake it again with `--bake-navmesh`.",
+            navmesh_path.display(),
+            navmesh.settings.agent_radius,
+            navmesh.settings.agent_height,
+        );
+    }
+}
+
+/// The path on disk of an asset loaded from the `assets` directory.
+fn asset_file_path<A: Asset>(handle: &Handle<A>) -> Option<PathBuf> {
+    let path = handle.path()?;
+    Some(PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/assets")).join(path.path()))
+}
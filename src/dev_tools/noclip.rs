@@ -0,0 +1,195 @@
+//! A free-flying spectator camera for moving around the level independently of the character controller.
+//!
+//! While noclip is active, the [`PlayerCamera`] is detached from the player, player input is blocked and
+//! the player's collider is disabled. When noclip is turned off again, the player is teleported to the camera.
+
+use std::any::Any as _;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_ahoy::camera::CharacterControllerCameraOf;
+use bevy_enhanced_input::prelude::*;
+use bevy_landmass::{Archipelago3d, FromAgentRadius as _, PointSampleDistance3d};
+
+use super::input::ToggleNoclip;
+use crate::{
+    PostPhysicsAppSystems,
+    gameplay::player::{
+        PLAYER_FLOAT_HEIGHT, PLAYER_RADIUS, Player,
+        camera::{CameraSensitivity, PlayerCamera},
+        input::BlocksInput,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_input_context::<NoclipInputContext>();
+    app.add_observer(toggle_noclip_on_input);
+    app.add_observer(adjust_noclip_speed);
+    app.add_systems(
+        Update,
+        fly_noclip_camera
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PostPhysicsAppSystems::Update),
+    );
+}
+
+/// Marks the [`PlayerCamera`] as flying around freely.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub(crate) struct Noclip {
+    /// Flying speed in meters per second.
+    pub(crate) speed: f32,
+}
+
+impl Noclip {
+    const DEFAULT_SPEED: f32 = 8.0;
+    const MIN_SPEED: f32 = 0.5;
+    const MAX_SPEED: f32 = 100.0;
+}
+
+impl Default for Noclip {
+    fn default() -> Self {
+        Self {
+            speed: Self::DEFAULT_SPEED,
+        }
+    }
+}
+
+#[derive(Debug, Component, Default)]
+struct NoclipInputContext;
+
+#[derive(Debug, InputAction)]
+#[action_output(Vec2)]
+struct NoclipMovement;
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+struct NoclipAscend;
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+struct NoclipDescend;
+
+#[derive(Debug, InputAction)]
+#[action_output(Vec2)]
+struct NoclipRotate;
+
+#[derive(Debug, InputAction)]
+#[action_output(f32)]
+struct AdjustNoclipSpeed;
+
+fn toggle_noclip_on_input(_on: On<Start<ToggleNoclip>>, mut commands: Commands) {
+    commands.run_system_cached(toggle_noclip);
+}
+
+/// Turns noclip on or off. Also used by the console's `noclip` command.
+pub(crate) fn toggle_noclip(
+    camera: Single<(Entity, &Transform, Has<Noclip>), With<PlayerCamera>>,
+    player: Single<Entity, With<Player>>,
+    archipelago: Single<&Archipelago3d>,
+    mut blocks_input: ResMut<BlocksInput>,
+    mut commands: Commands,
+) {
+    let (camera, camera_transform, is_noclipping) = camera.into_inner();
+    let player = *player;
+    if is_noclipping {
+        // Snap the player to the navmesh below the camera if possible so that we don't end up inside a wall.
+        let translation = archipelago
+            .sample_point(
+                camera_transform.translation,
+                &PointSampleDistance3d::from_agent_radius(PLAYER_RADIUS * 2.0),
+            )
+            .map(|sampled_point| sampled_point.point() + Vec3::Y * PLAYER_FLOAT_HEIGHT)
+            .unwrap_or(camera_transform.translation);
+        commands
+            .entity(camera)
+            .remove::<Noclip>()
+            .remove_with_requires::<NoclipInputContext>()
+            .despawn_related::<Actions<NoclipInputContext>>()
+            .insert(CharacterControllerCameraOf::new(player));
+        commands
+            .entity(player)
+            .remove::<ColliderDisabled>()
+            .insert((
+                Transform::from_translation(translation),
+                LinearVelocity::ZERO,
+            ));
+        blocks_input.remove(&toggle_noclip.type_id());
+        info!("Noclip disabled");
+    } else {
+        commands
+            .entity(camera)
+            .remove::<CharacterControllerCameraOf>()
+            .insert((
+                Noclip::default(),
+                NoclipInputContext,
+                actions!(NoclipInputContext[
+                    (
+                        Action::<NoclipMovement>::new(),
+                        DeadZone::default(),
+                        Bindings::spawn((Cardinal::wasd_keys(), Axial::left_stick())),
+                    ),
+                    (
+                        Action::<NoclipAscend>::new(),
+                        bindings![KeyCode::Space, GamepadButton::South],
+                    ),
+                    (
+                        Action::<NoclipDescend>::new(),
+                        bindings![KeyCode::ControlLeft, GamepadButton::LeftTrigger2],
+                    ),
+                    (
+                        Action::<NoclipRotate>::new(),
+                        Bindings::spawn((
+                            Spawn((Binding::mouse_motion(), Scale::splat(0.002))),
+                            Axial::right_stick().with((Scale::splat(0.05), DeadZone::default())),
+                        )),
+                    ),
+                    (
+                        Action::<AdjustNoclipSpeed>::new(),
+                        bindings![(Binding::mouse_wheel(), SwizzleAxis::YXZ)],
+                    ),
+                ]),
+            ));
+        commands.entity(player).insert(ColliderDisabled);
+        blocks_input.insert(toggle_noclip.type_id());
+        info!("Noclip enabled");
+    }
+}
+
+fn adjust_noclip_speed(fire: On<Fire<AdjustNoclipSpeed>>, mut noclip: Single<&mut Noclip>) {
+    // Scale exponentially so that the speed feels equally adjustable at low and high values.
+    let factor = 1.25_f32.powf(fire.value.signum());
+    noclip.speed = (noclip.speed * factor).clamp(Noclip::MIN_SPEED, Noclip::MAX_SPEED);
+}
+
+fn fly_noclip_camera(
+    camera: Single<(&mut Transform, &Noclip), With<PlayerCamera>>,
+    movement: Single<&Action<NoclipMovement>>,
+    ascend: Single<&Action<NoclipAscend>>,
+    descend: Single<&Action<NoclipDescend>>,
+    rotate: Single<&Action<NoclipRotate>>,
+    sensitivity: Res<CameraSensitivity>,
+    time: Res<Time>,
+) {
+    let (mut transform, noclip) = camera.into_inner();
+
+    let rotation = **rotate * sensitivity.0;
+    let (yaw, pitch, _roll) = transform.rotation.to_euler(EulerRot::YXZ);
+    const MAX_PITCH: f32 = std::f32::consts::FRAC_PI_2 - 0.01;
+    let yaw = yaw - rotation.x;
+    let pitch = (pitch - rotation.y).clamp(-MAX_PITCH, MAX_PITCH);
+    transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
+
+    let movement = **movement;
+    let mut vertical = 0.0;
+    if **ascend {
+        vertical += 1.0;
+    }
+    if **descend {
+        vertical -= 1.0;
+    }
+    let direction =
+        transform.forward() * movement.y + transform.right() * movement.x + Vec3::Y * vertical;
+    transform.translation += direction.clamp_length_max(1.0) * noclip.speed * time.delta_secs();
+}
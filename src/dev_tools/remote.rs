@@ -0,0 +1,265 @@
+//! Game-specific methods for the [Bevy Remote Protocol](bevy::remote), so that editor tooling and automated
+//! playtests can drive the game without poking at raw components.
+//!
+//! All methods live under the `jam/` namespace, e.g.
+//! `{"jsonrpc": "2.0", "id": 0, "method": "jam/teleport_player", "params": {"translation": [0, 2, 0]}}`.
+
+use avian3d::prelude::*;
+use bevy::{
+    prelude::*,
+    remote::{
+        BrpError, BrpResult, RemoteMethodSystemId, RemoteMethods,
+        builtin_methods::{parse, parse_some},
+    },
+};
+use bevy_trenchbroom::class::{QuakeClassType, ReflectQuakeClass};
+use bevy_yarnspinner::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    Pause,
+    dev_tools::spawn_menu::spawn_dev_class,
+    gameplay::{
+        level::LevelAssets,
+        player::{Player, dialogue::start_dialogue},
+    },
+    menus::{Menu, pause::go_back},
+    screens::{
+        Screen,
+        gameplay::{open_pause_menu, pause, spawn_pause_overlay},
+        loading::LoadingScreen,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_remote_method("jam/teleport_player", teleport_player)
+        .add_remote_method("jam/start_dialogue", start_dialogue_method)
+        .add_remote_method("jam/list_props", list_props)
+        .add_remote_method("jam/spawn_class", spawn_class)
+        .add_remote_method("jam/set_pause", set_pause)
+        .add_remote_method("jam/screen_state", screen_state)
+        .add_remote_method("jam/load_level", load_level);
+}
+
+trait AddRemoteMethod {
+    /// Registers a one-shot system as an instant BRP method. Requires the [`RemotePlugin`](bevy::remote::RemotePlugin)
+    /// to be added first.
+    fn add_remote_method<M>(
+        &mut self,
+        name: &str,
+        system: impl IntoSystem<In<Option<Value>>, BrpResult, M> + 'static,
+    ) -> &mut Self;
+}
+
+impl AddRemoteMethod for App {
+    fn add_remote_method<M>(
+        &mut self,
+        name: &str,
+        system: impl IntoSystem<In<Option<Value>>, BrpResult, M> + 'static,
+    ) -> &mut Self {
+        let system = self.world_mut().register_system(system);
+        self.world_mut()
+            .resource_mut::<RemoteMethods>()
+            .insert(name, RemoteMethodSystemId::Instant(system));
+        self
+    }
+}
+
+#[derive(Deserialize)]
+struct TeleportPlayerParams {
+    translation: [f32; 3],
+}
+
+fn teleport_player(
+    In(params): In<Option<Value>>,
+    mut player: Query<(&mut Transform, &mut LinearVelocity), With<Player>>,
+) -> BrpResult {
+    let TeleportPlayerParams { translation } = parse_some(params)?;
+    let (mut transform, mut velocity) = player
+        .single_mut()
+        .map_err(|_| BrpError::internal("No player is spawned"))?;
+    transform.translation = Vec3::from_array(translation);
+    velocity.0 = Vec3::ZERO;
+    Ok(Value::Null)
+}
+
+#[derive(Deserialize)]
+struct StartDialogueParams {
+    node: String,
+}
+
+fn start_dialogue_method(
+    In(params): In<Option<Value>>,
+    dialogue_runner: Query<&DialogueRunner>,
+    mut commands: Commands,
+) -> BrpResult {
+    let StartDialogueParams { node } = parse_some(params)?;
+    let dialogue_runner = dialogue_runner
+        .single()
+        .map_err(|_| BrpError::internal("No dialogue runner is spawned"))?;
+    if dialogue_runner.is_running() {
+        return Err(BrpError::internal("A dialogue is already running"));
+    }
+    if !dialogue_runner.node_exists(&node) {
+        return Err(BrpError::internal(format!(
+            "No Yarn node named \"{node}\" exists"
+        )));
+    }
+    commands.run_system_cached_with(start_dialogue, node);
+    Ok(Value::Null)
+}
+
+#[derive(Serialize)]
+struct PropInfo {
+    entity: Entity,
+    classname: &'static str,
+    translation: [f32; 3],
+}
+
+/// Lists every spawned entity that belongs to a TrenchBroom point class.
+fn list_props(In(_params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    let classes: Vec<_> = registry
+        .iter_with_data::<ReflectQuakeClass>()
+        .filter(|(_, class)| matches!(class.erased_class.info.ty, QuakeClassType::Point))
+        .filter_map(|(registration, class)| {
+            let reflect_component = registration.data::<ReflectComponent>()?;
+            Some((class.erased_class.info.name, reflect_component))
+        })
+        .collect();
+
+    let mut props = Vec::new();
+    let mut query = world.query::<(Entity, &GlobalTransform)>();
+    for (entity, transform) in query.iter(world) {
+        let entity_ref = world.entity(entity);
+        let Some((classname, _)) = classes
+            .iter()
+            .find(|(_, reflect_component)| reflect_component.contains(entity_ref))
+        else {
+            continue;
+        };
+        props.push(PropInfo {
+            entity,
+            classname,
+            translation: transform.translation().to_array(),
+        });
+    }
+    serde_json::to_value(props).map_err(BrpError::internal)
+}
+
+#[derive(Deserialize)]
+struct SpawnClassParams {
+    classname: String,
+    translation: [f32; 3],
+    /// Rotation around the Y axis in degrees.
+    #[serde(default)]
+    yaw: f32,
+}
+
+fn spawn_class(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let SpawnClassParams {
+        classname,
+        translation,
+        yaw,
+    } = parse_some(params)?;
+    if world.resource::<State<Screen>>().get() != &Screen::Gameplay {
+        return Err(BrpError::internal("No map is loaded"));
+    }
+    let transform = Transform::from_translation(Vec3::from_array(translation))
+        .with_rotation(Quat::from_rotation_y(yaw.to_radians()));
+    let entity = spawn_dev_class(world, &classname, transform).map_err(BrpError::internal)?;
+    serde_json::to_value(entity).map_err(BrpError::internal)
+}
+
+#[derive(Deserialize)]
+struct SetPauseParams {
+    paused: bool,
+}
+
+/// Pauses or resumes the game the same way the pause menu does.
+fn set_pause(
+    In(params): In<Option<Value>>,
+    screen: Res<State<Screen>>,
+    paused: Res<State<Pause>>,
+    mut commands: Commands,
+) -> BrpResult {
+    let SetPauseParams {
+        paused: pause_requested,
+    } = parse_some(params)?;
+    if screen.get() != &Screen::Gameplay {
+        return Err(BrpError::internal(
+            "The game can only be paused during gameplay",
+        ));
+    }
+    if paused.get().0 == pause_requested {
+        return Ok(Value::Null);
+    }
+    if pause_requested {
+        commands.run_system_cached(pause);
+        commands.run_system_cached(spawn_pause_overlay);
+        commands.run_system_cached(open_pause_menu);
+    } else {
+        commands.run_system_cached(go_back);
+    }
+    Ok(Value::Null)
+}
+
+#[derive(Serialize)]
+struct ScreenStateInfo {
+    screen: String,
+    loading_screen: Option<String>,
+    menu: String,
+    paused: bool,
+}
+
+fn screen_state(
+    In(_params): In<Option<Value>>,
+    screen: Res<State<Screen>>,
+    loading_screen: Option<Res<State<LoadingScreen>>>,
+    menu: Res<State<Menu>>,
+    paused: Res<State<Pause>>,
+) -> BrpResult {
+    let info = ScreenStateInfo {
+        screen: format!("{:?}", screen.get()),
+        loading_screen: loading_screen.map(|loading_screen| format!("{:?}", loading_screen.get())),
+        menu: format!("{:?}", menu.get()),
+        paused: paused.get().0,
+    };
+    serde_json::to_value(info).map_err(BrpError::internal)
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct LoadLevelParams {
+    /// Whether to reload the `.map` file from disk first.
+    reload: bool,
+}
+
+/// Starts loading the level, either from the title screen or by respawning the current one.
+fn load_level(
+    In(params): In<Option<Value>>,
+    level_assets: Option<Res<LevelAssets>>,
+    asset_server: Res<AssetServer>,
+    screen: Res<State<Screen>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) -> BrpResult {
+    let LoadLevelParams { reload } = params.map(parse).transpose()?.unwrap_or_default();
+    if !matches!(screen.get(), Screen::Title | Screen::Gameplay) {
+        return Err(BrpError::internal(format!(
+            "Cannot load a level while on the {:?} screen",
+            screen.get()
+        )));
+    }
+    if reload {
+        let path = level_assets
+            .as_ref()
+            .and_then(|level_assets| level_assets.level.path())
+            .ok_or_else(|| BrpError::internal("The level was not loaded from a file"))?;
+        // Reload the whole `.map` file, not just its scene label.
+        asset_server.reload(path.without_label().clone_owned());
+    }
+    next_screen.set(Screen::Loading);
+    Ok(Value::Null)
+}
@@ -0,0 +1,320 @@
+//! A menu for spawning any point class at the crosshair without having to place it in TrenchBroom first.
+//! Press F5 to open it, scroll or use the arrow keys to select a class, and press the middle mouse button or
+//! Enter to spawn it. A preview of the selected class follows the crosshair.
+//!
+//! Everything spawned from the dev tools is marked with [`DevSpawned`] and can be cleared in bulk with Delete.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+use bevy_trenchbroom::prelude::*;
+
+use super::input::ToggleSpawnMenu;
+use crate::{
+    PostPhysicsAppSystems,
+    gameplay::player::{Player, camera::PlayerCamera},
+    props::manifest::LoadedPropManifest,
+    screens::Screen,
+    theme::palette::{HEADER_TEXT, LABEL_TEXT},
+    third_party::{
+        avian3d::CollisionLayer,
+        bevy_trenchbroom::{point_class_model_path, point_class_names, spawn_point_class},
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_input_context::<SpawnMenuInputContext>();
+    app.add_observer(toggle_spawn_menu)
+        .add_observer(select_spawn_class)
+        .add_observer(spawn_selected_class)
+        .add_observer(clear_dev_spawned_on_input);
+    app.add_systems(
+        Update,
+        (
+            (respawn_spawn_preview, update_spawn_menu_ui)
+                .run_if(any_match_filter::<Changed<SpawnMenu>>)
+                .in_set(PostPhysicsAppSystems::ChangeUi),
+            move_spawn_preview.in_set(PostPhysicsAppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Marks entities that were spawned through the dev tools rather than by the map.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub(crate) struct DevSpawned;
+
+/// Spawns a point class like [`spawn_point_class`] and marks it as [`DevSpawned`].
+pub(crate) fn spawn_dev_class(
+    world: &mut World,
+    classname: &str,
+    transform: Transform,
+) -> Result<Entity> {
+    let entity = spawn_point_class(world, classname, transform)?;
+    world.entity_mut(entity).insert(DevSpawned);
+    Ok(entity)
+}
+
+/// Despawns everything spawned through the dev tools and returns how many entities were despawned.
+/// Also used by the console's `clear_spawned` command.
+pub(crate) fn clear_dev_spawned(world: &mut World) -> usize {
+    let spawned: Vec<_> = world
+        .query_filtered::<Entity, With<DevSpawned>>()
+        .iter(world)
+        .collect();
+    for &entity in &spawned {
+        world.despawn(entity);
+    }
+    info!("Cleared {} dev-spawned entities", spawned.len());
+    spawned.len()
+}
+
+/// Where a class spawned at the crosshair ends up, or `None` if the crosshair is not pointing at anything.
+pub(crate) fn crosshair_spawn_transform(
+    camera: &GlobalTransform,
+    player: Entity,
+    spatial_query: &SpatialQuery,
+) -> Option<Transform> {
+    let camera = camera.compute_transform();
+    const MAX_SPAWN_DISTANCE: f32 = 100.0;
+    let hit = spatial_query.cast_ray(
+        camera.translation,
+        camera.forward(),
+        MAX_SPAWN_DISTANCE,
+        true,
+        &SpatialQueryFilter::from_mask([CollisionLayer::Default, CollisionLayer::Prop])
+            .with_excluded_entities([player]),
+    )?;
+    let translation = camera.translation + camera.forward() * hit.distance;
+    Some(Transform::from_translation(translation).with_rotation(facing_camera(&camera)))
+}
+
+/// Rotation around the Y axis facing the camera, so that spawned props face the player.
+pub(crate) fn facing_camera(camera: &Transform) -> Quat {
+    let (yaw, _pitch, _roll) = camera.rotation.to_euler(EulerRot::YXZ);
+    Quat::from_rotation_y(yaw + std::f32::consts::PI)
+}
+
+/// The open spawn menu.
+#[derive(Component, Debug)]
+struct SpawnMenu {
+    classes: Vec<String>,
+    selected: usize,
+}
+
+impl SpawnMenu {
+    fn selected_class(&self) -> Option<&str> {
+        self.classes.get(self.selected).map(String::as_str)
+    }
+}
+
+/// Shows the model of the selected class where it would be spawned. It only has a scene, no class component,
+/// so none of the class's observers run for it.
+#[derive(Component)]
+struct SpawnPreview;
+
+#[derive(Component)]
+struct SpawnMenuText;
+
+#[derive(Debug, Component, Default)]
+struct SpawnMenuInputContext;
+
+#[derive(Debug, InputAction)]
+#[action_output(f32)]
+struct SelectSpawnClass;
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+struct SpawnSelectedClass;
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+struct ClearDevSpawned;
+
+/// How many classes are listed above and below the selected one.
+const VISIBLE_NEIGHBORS: usize = 8;
+
+fn toggle_spawn_menu(
+    _on: On<Start<ToggleSpawnMenu>>,
+    menu: Option<Single<Entity, With<SpawnMenu>>>,
+    preview: Option<Single<Entity, With<SpawnPreview>>>,
+    screen: Res<State<Screen>>,
+    type_registry: Res<AppTypeRegistry>,
+    props: LoadedPropManifest,
+    mut commands: Commands,
+) {
+    if let Some(menu) = menu {
+        commands.entity(*menu).despawn();
+        if let Some(preview) = preview {
+            commands.entity(*preview).despawn();
+        }
+        return;
+    }
+    if screen.get() != &Screen::Gameplay {
+        return;
+    }
+    // There can only be one player.
+    let classes = point_class_names(&type_registry.read(), props.get())
+        .into_iter()
+        .filter(|classname| classname != Player::CLASS_INFO.name)
+        .collect();
+    commands.spawn((
+        Name::new("Spawn Menu"),
+        SpawnMenu {
+            classes,
+            selected: 0,
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(16.0),
+            top: Val::Px(16.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(8.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        GlobalZIndex(5),
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        SpawnMenuInputContext,
+        actions!(SpawnMenuInputContext[
+            (
+                Action::<SelectSpawnClass>::new(),
+                bindings![
+                    (Binding::mouse_wheel(), SwizzleAxis::YXZ),
+                    KeyCode::ArrowUp,
+                    (KeyCode::ArrowDown, Negate::all()),
+                ],
+            ),
+            (
+                Action::<SpawnSelectedClass>::new(),
+                bindings![MouseButton::Middle, KeyCode::Enter],
+            ),
+            (Action::<ClearDevSpawned>::new(), bindings![KeyCode::Delete]),
+        ]),
+        children![
+            (
+                Name::new("Spawn Menu Controls"),
+                Text::new(
+                    "Wheel / Up / Down: select\nMiddle mouse / Enter: spawn\nDelete: clear spawned"
+                ),
+                TextFont::from_font_size(14.0),
+                TextColor(LABEL_TEXT),
+            ),
+            (
+                Name::new("Spawn Menu Classes"),
+                SpawnMenuText,
+                Text::default(),
+                TextFont::from_font_size(16.0),
+                TextColor(HEADER_TEXT),
+            ),
+        ],
+    ));
+}
+
+fn select_spawn_class(start: On<Start<SelectSpawnClass>>, mut menu: Single<&mut SpawnMenu>) {
+    let len = menu.classes.len();
+    if len == 0 {
+        return;
+    }
+    // Scrolling up or pressing the up arrow moves the selection up the list.
+    menu.selected = if start.value > 0.0 {
+        (menu.selected + len - 1) % len
+    } else {
+        (menu.selected + 1) % len
+    };
+}
+
+fn update_spawn_menu_ui(
+    menu: Single<&SpawnMenu>,
+    mut text: Single<&mut Text, With<SpawnMenuText>>,
+) {
+    let first = menu.selected.saturating_sub(VISIBLE_NEIGHBORS);
+    let last = (menu.selected + VISIBLE_NEIGHBORS + 1).min(menu.classes.len());
+    text.0 = (first..last)
+        .map(|index| {
+            let marker = if index == menu.selected { ">" } else { " " };
+            format!("{marker} {}", menu.classes[index])
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+}
+
+fn respawn_spawn_preview(
+    menu: Single<&SpawnMenu>,
+    preview: Option<Single<Entity, With<SpawnPreview>>>,
+    type_registry: Res<AppTypeRegistry>,
+    props: LoadedPropManifest,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    if let Some(preview) = preview {
+        commands.entity(*preview).despawn();
+    }
+    let Some(classname) = menu.selected_class() else {
+        return;
+    };
+    let mut preview = commands.spawn((
+        Name::new("Spawn Preview"),
+        SpawnPreview,
+        Transform::default(),
+        Visibility::Hidden,
+        DespawnOnExit(Screen::Gameplay),
+    ));
+    if let Some(model_path) = point_class_model_path(&type_registry.read(), props.get(), classname)
+    {
+        preview.insert(SceneRoot(asset_server.load(format!("{model_path}#Scene0"))));
+    }
+}
+
+fn move_spawn_preview(
+    preview: Single<(&mut Transform, &mut Visibility), With<SpawnPreview>>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    player: Single<Entity, With<Player>>,
+    spatial_query: SpatialQuery,
+    mut gizmos: Gizmos,
+) {
+    let (mut transform, mut visibility) = preview.into_inner();
+    let Some(spawn_transform) = crosshair_spawn_transform(&camera, *player, &spatial_query) else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    *transform = spawn_transform;
+    *visibility = Visibility::Inherited;
+    // Classes without a model would otherwise have no preview at all.
+    gizmos.sphere(
+        Isometry3d::from_translation(spawn_transform.translation),
+        0.1,
+        HEADER_TEXT,
+    );
+}
+
+fn spawn_selected_class(
+    _on: On<Start<SpawnSelectedClass>>,
+    menu: Single<&SpawnMenu>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    player: Single<Entity, With<Player>>,
+    spatial_query: SpatialQuery,
+    mut commands: Commands,
+) {
+    let Some(classname) = menu.selected_class().map(str::to_string) else {
+        return;
+    };
+    let Some(transform) = crosshair_spawn_transform(&camera, *player, &spatial_query) else {
+        return;
+    };
+    commands.queue(
+        move |world: &mut World| match spawn_dev_class(world, &classname, transform) {
+            Ok(entity) => info!("Spawned {classname} ({entity})"),
+            Err(error) => warn!("Failed to spawn {classname}: {error}"),
+        },
+    );
+}
+
+fn clear_dev_spawned_on_input(_on: On<Start<ClearDevSpawned>>, mut commands: Commands) {
+    commands.queue(|world: &mut World| {
+        clear_dev_spawned(world);
+    });
+}
@@ -1,9 +1,20 @@
 //! Spawn the main level.
 
 use crate::{
-    asset_tracking::LoadResource, audio::MusicPool, gameplay::npc::NPC_RADIUS, screens::Screen,
+    asset_tracking::LoadResource,
+    audio::{MusicPool, caption::Caption},
+    gameplay::{
+        npc::{NPC_HEIGHT, NPC_RADIUS},
+        objectives::Mission,
+    },
+    props::nav_obstacle::CarvedNavmesh,
+    screens::Screen,
+};
+use avian3d::prelude::*;
+use bevy::{
+    ecs::{entity::EntityHashSet, system::SystemParam},
+    prelude::*,
 };
-use bevy::prelude::*;
 use bevy_landmass::prelude::*;
 use bevy_rerecast::prelude::*;
 use bevy_seedling::prelude::*;
@@ -16,7 +27,11 @@ pub(super) fn plugin(app: &mut App) {
 }
 
 /// A system that spawns the main level.
-pub(crate) fn spawn_level(mut commands: Commands, level_assets: Res<LevelAssets>) {
+pub(crate) fn spawn_level(
+    mut commands: Commands,
+    level_assets: Res<LevelAssets>,
+    mut navmeshes: ResMut<Assets<Navmesh>>,
+) -> Result {
     commands.spawn((
         Name::new("Level"),
         SceneRoot(level_assets.level.clone()),
@@ -25,7 +40,8 @@ pub(crate) fn spawn_level(mut commands: Commands, level_assets: Res<LevelAssets>
         children![(
             Name::new("Level Music"),
             SamplePlayer::new(level_assets.music.clone()).looping(),
-            MusicPool
+            MusicPool,
+            Caption::new("[rain]"),
         )],
     ));
 
@@ -37,21 +53,65 @@ pub(crate) fn spawn_level(mut commands: Commands, level_assets: Res<LevelAssets>
         ))
         .id();
 
+    // Resting props are carved into a copy of the level's navmesh, so the island walks on that one.
+    let carved_navmesh = CarvedNavmesh::from_level(&level_assets, &mut navmeshes)?;
     commands.spawn((
         Name::new("Main Level Island"),
         DespawnOnExit(Screen::Gameplay),
         Island3dBundle {
             island: Island,
             archipelago_ref: ArchipelagoRef3d::new(archipelago),
-            nav_mesh: NavMeshHandle3d(level_assets.navmesh.clone()),
+            nav_mesh: NavMeshHandle3d(carved_navmesh.handle.clone()),
         },
     ));
+    commands.insert_resource(carved_navmesh);
+    Ok(())
 }
 
 #[derive(Component, Debug, Reflect)]
 #[reflect(Component)]
 pub(crate) struct Level;
 
+/// The map of the main level, relative to `assets`.
+pub(crate) const LEVEL_MAP_PATH: &str = "maps/volta_i/volta_i.map";
+/// The navmesh baked from [`LEVEL_MAP_PATH`], relative to `assets`.
+pub(crate) const LEVEL_NAVMESH_PATH: &str = "maps/volta_i/volta_i.nav";
+
+/// The settings the level's navmesh is baked with. These have to match the agents that walk on it.
+pub(crate) fn navmesh_settings(colliders: EntityHashSet) -> NavmeshSettings {
+    NavmeshSettings {
+        filter: Some(colliders),
+        ..NavmeshSettings::from_agent_3d(NPC_RADIUS, NPC_HEIGHT)
+    }
+}
+
+/// Finds the colliders the level's navmesh is baked from.
+#[derive(SystemParam)]
+pub(crate) struct NavmeshColliders<'w, 's> {
+    colliders:
+        Query<'w, 's, (Entity, Option<&'static ColliderOf>), (With<Collider>, Without<Sensor>)>,
+    bodies: Query<'w, 's, &'static RigidBody>,
+}
+
+impl NavmeshColliders<'_, '_> {
+    /// The colliders of the level geometry, which is everything static.
+    /// Characters and props moving around on the navmesh don't belong into it.
+    pub(crate) fn collect(&self) -> EntityHashSet {
+        self.colliders
+            .iter()
+            .filter(|(_, collider_of)| match collider_of {
+                // Colliders without a body never move.
+                None => true,
+                Some(ColliderOf { body }) => self
+                    .bodies
+                    .get(*body)
+                    .is_ok_and(|rigid_body| rigid_body.is_static()),
+            })
+            .map(|(entity, _)| entity)
+            .collect()
+    }
+}
+
 /// A [`Resource`] that contains all the assets needed to spawn the level.
 /// We use this to preload assets before the level is spawned.
 #[derive(Resource, Asset, Clone, TypePath)]
@@ -61,6 +121,8 @@ pub(crate) struct LevelAssets {
     #[dependency]
     pub(crate) navmesh: Handle<Navmesh>,
     #[dependency]
+    pub(crate) mission: Handle<Mission>,
+    #[dependency]
     pub(crate) music: Handle<AudioSample>,
     #[dependency]
     pub(crate) env_map_specular: Handle<Image>,
@@ -74,9 +136,10 @@ impl FromWorld for LevelAssets {
 
         Self {
             // Our main level is inspired by the TheDarkMod fan mission [Volta I: The Stone](https://www.thedarkmod.com/missiondetails/?internalName=volta1_3)
-            level: assets.load("maps/volta_i/volta_i.map#Scene"),
-            // You can regenerate the navmesh by using `bevy_rerecast_editor`
-            navmesh: assets.load("maps/volta_i/volta_i.nav"),
+            level: assets.load(format!("{LEVEL_MAP_PATH}#Scene")),
+            // You can regenerate the navmesh with `cargo run -- --bake-navmesh` or by using `bevy_rerecast_editor`
+            navmesh: assets.load(LEVEL_NAVMESH_PATH),
+            mission: assets.load("maps/volta_i/volta_i.mission.ron"),
             music: assets.load("audio/music/Ambiance_Rain_Calm_Loop_Stereo.ogg"),
             env_map_specular: assets.load("cubemaps/NightSkyHDRI001_4K-HDR_specular.ktx2"),
             env_map_diffuse: assets.load("cubemaps/NightSkyHDRI001_4K-HDR_diffuse.ktx2"),
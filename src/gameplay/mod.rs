@@ -9,14 +9,18 @@ mod animation;
 pub(crate) mod crosshair;
 pub(crate) mod level;
 pub(crate) mod npc;
+pub(crate) mod objectives;
 pub(crate) mod player;
+pub(crate) mod readable;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         animation::plugin,
         crosshair::plugin,
         npc::plugin,
+        objectives::plugin,
         player::plugin,
+        readable::plugin,
         // This plugin preloads the level,
         // so make sure to add it last.
         level::plugin,
@@ -2,7 +2,10 @@
 
 use avian3d::prelude::*;
 use bevy::prelude::*;
-use bevy_ahoy::input::GlobalMovement;
+use bevy_ahoy::{
+    input::GlobalMovement,
+    prelude::{Jump, Mantle},
+};
 use bevy_enhanced_input::prelude::*;
 use bevy_landmass::{
     TargetReachedCondition,
@@ -12,11 +15,14 @@ use bevy_landmass::{
 };
 
 use crate::{
-    gameplay::{npc::NPC_SPEED, player::navmesh_position::LastValidPlayerNavmeshPosition},
+    gameplay::{
+        npc::NPC_SPEED,
+        player::{dialogue::InConversation, navmesh_position::LastValidPlayerNavmeshPosition},
+    },
     screens::Screen,
 };
 
-use super::{NPC_FLOAT_HEIGHT, NPC_RADIUS, Npc};
+use super::{NPC_FLOAT_HEIGHT, NPC_RADIUS, Npc, nav_link::TraversingNavLink};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
@@ -26,6 +32,8 @@ pub(super) fn plugin(app: &mut App) {
             set_controller_velocity,
             rotate_npc,
             update_agent_target,
+            start_traversing_nav_links,
+            traverse_nav_links,
         )
             .chain()
             .run_if(in_state(Screen::Gameplay)),
@@ -53,6 +61,23 @@ fn setup_npc_agent(
                     span: MockSpan::Updates(1),
                     enabled: false
                 }
+            ), (
+                // Jumping and mantling are only used for off-mesh links, see `nav_link.rs`.
+                Action::<Jump>::new(),
+                ActionMock {
+                    state: ActionState::None,
+                    value: false.into(),
+                    span: MockSpan::Updates(1),
+                    enabled: false
+                }
+            ), (
+                Action::<Mantle>::new(),
+                ActionMock {
+                    state: ActionState::None,
+                    value: false.into(),
+                    span: MockSpan::Updates(1),
+                    enabled: false
+                }
             )]
         ),
     ));
@@ -81,7 +106,7 @@ struct NpcInputContext;
 
 #[derive(Component, Debug, Reflect)]
 #[reflect(Component)]
-struct WantsToFollowPlayer;
+pub(super) struct WantsToFollowPlayer;
 
 fn update_agent_target(
     mut agents: Query<&mut AgentTarget3d, With<WantsToFollowPlayer>>,
@@ -103,11 +128,12 @@ struct AgentOf(Entity);
 #[derive(Component, Deref, Debug, Reflect)]
 #[reflect(Component)]
 #[relationship_target(relationship = AgentOf)]
-struct Agent(Entity);
+pub(super) struct Agent(Entity);
 
 /// Use the desired velocity as the agent's velocity.
 fn set_controller_velocity(
-    mut agent_query: Query<(&Agent, &Actions<NpcInputContext>)>,
+    // NPCs traversing an off-mesh link are steered by `nav_link.rs` instead.
+    mut agent_query: Query<(&Agent, &Actions<NpcInputContext>), Without<TraversingNavLink>>,
     mut action_mocks: Query<&mut ActionMock, With<Action<GlobalMovement>>>,
     desired_velocity_query: Query<&LandmassAgentDesiredVelocity>,
 ) {
@@ -127,7 +153,8 @@ fn set_controller_velocity(
 }
 
 fn rotate_npc(
-    mut agent_query: Query<(&mut Transform, &LinearVelocity), With<Npc>>,
+    // NPCs in a conversation face the player instead, see `conversation.rs`.
+    mut agent_query: Query<(&mut Transform, &LinearVelocity), (With<Npc>, Without<InConversation>)>,
     time: Res<Time>,
 ) {
     for (mut transform, velocity) in &mut agent_query {
@@ -147,3 +174,73 @@ fn sync_agent_velocity(mut agent_query: Query<(&LinearVelocity, &mut LandmassVel
         landmass_velocity.velocity = avian_velocity.0;
     }
 }
+
+fn start_traversing_nav_links(
+    mut commands: Commands,
+    agents: Query<(Entity, &ReachedAnimationLink3d, &AgentOf), Without<UsingAnimationLink>>,
+    links: Query<&AnimationLink3d>,
+) {
+    for (agent, reached, npc) in &agents {
+        let Some(traversal) = links
+            .get(reached.link_entity)
+            .ok()
+            .and_then(|link| TraversingNavLink::start(link, reached.end_point))
+        else {
+            continue;
+        };
+        commands.entity(agent).insert(UsingAnimationLink);
+        commands.entity(**npc).insert(traversal);
+    }
+}
+
+/// Mocks the inputs NPCs need to traverse their nav link, see `nav_link.rs`.
+fn traverse_nav_links(
+    mut commands: Commands,
+    mut npcs: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &mut TraversingNavLink,
+            &Agent,
+            &Actions<NpcInputContext>,
+        ),
+        With<Npc>,
+    >,
+    mut movement: Query<&mut ActionMock, With<Action<GlobalMovement>>>,
+    mut jump: Query<&mut ActionMock, (With<Action<Jump>>, Without<Action<GlobalMovement>>)>,
+    mut mantle: Query<
+        &mut ActionMock,
+        (
+            With<Action<Mantle>>,
+            Without<Action<GlobalMovement>>,
+            Without<Action<Jump>>,
+        ),
+    >,
+    time: Res<Time>,
+) {
+    for (npc, transform, mut traversal, agent, actions) in &mut npcs {
+        // The end of the link is on the navmesh, but the NPC floats above it.
+        let feet = transform.translation() - Vec3::Y * NPC_FLOAT_HEIGHT;
+        let Some(inputs) = traversal.advance(time.delta(), feet) else {
+            commands.entity(npc).remove::<TraversingNavLink>();
+            commands.entity(**agent).remove::<UsingAnimationLink>();
+            continue;
+        };
+
+        if let Some(direction) = inputs.direction {
+            if let Some(mut mock) = movement.iter_many_mut(actions).fetch_next() {
+                *mock = ActionMock::once(ActionState::Fired, *direction);
+            }
+        }
+        if inputs.jump {
+            if let Some(mut mock) = jump.iter_many_mut(actions).fetch_next() {
+                *mock = ActionMock::once(ActionState::Fired, true);
+            }
+        }
+        if inputs.mantle {
+            if let Some(mut mock) = mantle.iter_many_mut(actions).fetch_next() {
+                *mock = ActionMock::once(ActionState::Fired, true);
+            }
+        }
+    }
+}
@@ -1,155 +1,74 @@
-//! NPC animation handling.
+//! NPC animation handling. The animations themselves are described by the state machine in [`NPC_ANIMATIONS`](super::assets::NPC_ANIMATIONS).
 
-use std::time::Duration;
+use std::f32::consts::{PI, TAU};
 
 use avian3d::prelude::LinearVelocity;
 use bevy::prelude::*;
 use bevy_ahoy::CharacterControllerState;
 
 use crate::{
-    PostPhysicsAppSystems,
-    animation::{AnimationState, AnimationStateTransition},
-    gameplay::animation::AnimationPlayers,
-    screens::Screen,
+    PostPhysicsAppSystems, animation::state_machine::AnimationController, screens::Screen,
 };
 
-use super::assets::NpcAssets;
+use super::Npc;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         Update,
-        play_animations
+        update_animation_parameters
             .run_if(in_state(Screen::Gameplay))
-            .in_set(PostPhysicsAppSystems::PlayAnimations),
+            .before(PostPhysicsAppSystems::PlayAnimations),
     );
 }
 
-#[derive(Component, Debug, Reflect)]
+/// The yaw of an NPC in the previous frame, which its turn rate is derived from.
+#[derive(Component, Debug, Default, Reflect)]
 #[reflect(Component)]
-struct NpcAnimations {
-    idle: AnimationNodeIndex,
-    walk: AnimationNodeIndex,
-    run: AnimationNodeIndex,
-}
+pub(super) struct PreviousYaw(Option<f32>);
 
-pub(crate) fn setup_npc_animations(
-    add: On<Add, AnimationPlayers>,
-    q_anim_players: Query<&AnimationPlayers>,
-    mut commands: Commands,
-    assets: Res<NpcAssets>,
-    mut graphs: ResMut<Assets<AnimationGraph>>,
+fn update_animation_parameters(
+    mut query: Query<
+        (
+            &mut AnimationController,
+            &LinearVelocity,
+            &CharacterControllerState,
+            &Transform,
+            &mut PreviousYaw,
+        ),
+        With<Npc>,
+    >,
+    time: Res<Time>,
 ) {
-    let anim_players = q_anim_players.get(add.entity).unwrap();
-    for anim_player in anim_players.iter() {
-        let (graph, indices) = AnimationGraph::from_clips([
-            assets.run_animation.clone(),
-            assets.idle_animation.clone(),
-            assets.walk_animation.clone(),
-        ]);
-        let [run_index, idle_index, walk_index] = indices.as_slice() else {
-            unreachable!()
-        };
-        let graph_handle = graphs.add(graph);
+    for (mut controller, velocity, state, transform, mut previous_yaw) in &mut query {
+        controller.set_float("speed", velocity.xz().length());
+        controller.set_bool("grounded", state.grounded.is_some());
 
-        let animations = NpcAnimations {
-            idle: *idle_index,
-            walk: *walk_index,
-            run: *run_index,
+        let (yaw, _, _) = transform.rotation.to_euler(EulerRot::YXZ);
+        let turn_rate = match previous_yaw.0.replace(yaw) {
+            Some(previous) if time.delta_secs() > 0.0 => {
+                turn_angle(previous, yaw).abs() / time.delta_secs()
+            }
+            _ => 0.0,
         };
-        let transitions = AnimationTransitions::new();
-        commands.entity(anim_player).insert((
-            animations,
-            AnimationGraphHandle(graph_handle),
-            transitions,
-        ));
+        controller.set_float("turn_rate", turn_rate);
     }
 }
 
-/// Managed by [`play_animations`]
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub(crate) enum NpcAnimationState {
-    Standing,
-    Airborne,
-    Walking(f32),
-    Running(f32),
+/// The signed angle to turn by to get from yaw `from` to yaw `to`, taking the shorter way around.
+fn turn_angle(from: f32, to: f32) -> f32 {
+    (to - from + PI).rem_euclid(TAU) - PI
 }
 
-fn play_animations(
-    mut query: Query<(
-        &mut AnimationState<NpcAnimationState>,
-        &LinearVelocity,
-        &CharacterControllerState,
-        &AnimationPlayers,
-    )>,
-    mut q_animation: Query<(
-        &NpcAnimations,
-        &mut AnimationPlayer,
-        &mut AnimationTransitions,
-    )>,
-) {
-    for (mut animating_state, velocity, state, anim_players) in &mut query {
-        let mut iter = q_animation.iter_many_mut(anim_players.iter());
-        while let Some((animations, mut anim_player, mut transitions)) = iter.fetch_next() {
-            match animating_state.update_by_discriminant({
-                let speed = velocity.length();
-                if state.grounded.is_none() {
-                    NpcAnimationState::Airborne
-                } else if speed > 4.5 {
-                    NpcAnimationState::Running(speed)
-                } else if speed > 0.01 {
-                    NpcAnimationState::Walking(speed)
-                } else {
-                    NpcAnimationState::Standing
-                }
-            }) {
-                AnimationStateTransition::Maintain { state } => {
-                    if let NpcAnimationState::Running(speed) | NpcAnimationState::Walking(speed) =
-                        state
-                    {
-                        if let Some((_index, playing_animation)) =
-                            anim_player.playing_animations_mut().next()
-                        {
-                            let anim_speed = (speed / 3.0).max(0.3);
-                            playing_animation.set_speed(anim_speed);
-                        }
-                    }
-                }
-                AnimationStateTransition::Alter {
-                    // We don't need the old state here, but it's available for transition
-                    // animations.
-                    old_state: _,
-                    state,
-                } => match state {
-                    NpcAnimationState::Airborne => {
-                        transitions
-                            .play(&mut anim_player, animations.run, Duration::from_millis(200))
-                            .repeat();
-                    }
-                    NpcAnimationState::Standing => {
-                        transitions
-                            .play(
-                                &mut anim_player,
-                                animations.idle,
-                                Duration::from_millis(500),
-                            )
-                            .repeat();
-                    }
-                    NpcAnimationState::Walking(_speed) => {
-                        transitions
-                            .play(
-                                &mut anim_player,
-                                animations.walk,
-                                Duration::from_millis(300),
-                            )
-                            .repeat();
-                    }
-                    NpcAnimationState::Running(_speed) => {
-                        transitions
-                            .play(&mut anim_player, animations.run, Duration::from_millis(400))
-                            .repeat();
-                    }
-                },
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns_the_shorter_way_around() {
+        assert!((turn_angle(0.5, 1.0) - 0.5).abs() < 1e-6);
+        assert!((turn_angle(1.0, 0.5) + 0.5).abs() < 1e-6);
+        // Across the seam at ±π.
+        assert!((turn_angle(PI - 0.1, -PI + 0.1) - 0.2).abs() < 1e-5);
+        assert!((turn_angle(-PI + 0.1, PI - 0.1) + 0.2).abs() < 1e-5);
     }
 }
@@ -5,7 +5,8 @@ use bevy_seedling::sample::AudioSample;
 use bevy_shuffle_bag::ShuffleBag;
 
 use crate::{
-    asset_tracking::LoadResource, third_party::bevy_trenchbroom::GetTrenchbroomModelPath as _,
+    animation::state_machine::AnimationStateMachine, asset_tracking::LoadResource,
+    third_party::bevy_trenchbroom::GetTrenchbroomModelPath as _,
 };
 
 use super::Npc;
@@ -14,17 +15,16 @@ pub(super) fn plugin(app: &mut App) {
     app.load_resource::<NpcAssets>();
 }
 
+/// The animation state machine of the NPC.
+pub(super) const NPC_ANIMATIONS: &str = "animations/fox.anim.ron";
+
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub(crate) struct NpcAssets {
     #[dependency]
     pub(crate) _model: Handle<Scene>,
     #[dependency]
-    pub(crate) idle_animation: Handle<AnimationClip>,
-    #[dependency]
-    pub(crate) walk_animation: Handle<AnimationClip>,
-    #[dependency]
-    pub(crate) run_animation: Handle<AnimationClip>,
+    pub(crate) _animations: Handle<AnimationStateMachine>,
     #[dependency]
     pub(crate) steps: ShuffleBag<Handle<AudioSample>>,
 }
@@ -41,9 +41,7 @@ impl FromWorld for NpcAssets {
                     settings.load_materials = RenderAssetUsages::RENDER_WORLD;
                 },
             ),
-            run_animation: assets.load(Npc::animation_path(0)),
-            idle_animation: assets.load(Npc::animation_path(1)),
-            walk_animation: assets.load(Npc::animation_path(2)),
+            _animations: assets.load(NPC_ANIMATIONS),
             steps: ShuffleBag::try_new(
                 [
                     assets.load("audio/sound_effects/run/Footsteps_Rock_Run_01.ogg"),
@@ -0,0 +1,355 @@
+//! Barks are short lines NPCs say on their own when something happens around them, e.g. when the player approaches.
+//!
+//! Unlike conversations, barks don't run a [`DialogueRunner`](bevy_yarnspinner::prelude::DialogueRunner) and never
+//! block the player's input. Each [`BarkKind`] is a node in `dialogue/barks.yarn`, and the NPC says a random line
+//! of it as a subtitle floating above its head. Other systems can make an NPC bark by triggering [`Bark`] on it.
+
+use std::time::Duration;
+
+use avian_pickup::output::PropThrown;
+use avian3d::prelude::*;
+use bevy::{platform::collections::HashMap, prelude::*, ui::Val::*};
+use bevy_yarnspinner::prelude::*;
+use rand::seq::IndexedRandom as _;
+
+use crate::{
+    PostPhysicsAppSystems,
+    gameplay::player::{
+        Player,
+        camera::{PlayerCamera, WorldModelCamera},
+        dialogue::InConversation,
+    },
+    screens::Screen,
+    third_party::{avian3d::CollisionLayer, bevy_yarnspinner::is_dialogue_running},
+};
+
+use super::NPC_HALF_HEIGHT;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(play_bark);
+    app.add_observer(interrupt_bark_for_conversation);
+    app.add_systems(
+        Update,
+        (
+            bark_when_player_approaches,
+            bark_when_prop_thrown.run_if(on_message::<PropThrown>),
+            bark_when_losing_sight_of_player,
+        )
+            .run_if(in_state(Screen::Gameplay).and(not(is_dialogue_running)))
+            .in_set(PostPhysicsAppSystems::Update),
+    );
+    app.add_systems(
+        Update,
+        (finish_barks, place_bark_subtitles)
+            .chain()
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PostPhysicsAppSystems::ChangeUi),
+    );
+}
+
+/// The situations NPCs bark about.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum BarkKind {
+    PlayerApproached,
+    PropThrown,
+    LostSightOfPlayer,
+}
+
+impl BarkKind {
+    /// The Yarn node containing the lines for this bark.
+    fn node(self) -> &'static str {
+        match self {
+            Self::PlayerApproached => "BarkPlayerApproached",
+            Self::PropThrown => "BarkPropThrown",
+            Self::LostSightOfPlayer => "BarkLostSightOfPlayer",
+        }
+    }
+
+    /// Barks with a higher priority interrupt the ones with a lower priority.
+    fn priority(self) -> u8 {
+        match self {
+            Self::PlayerApproached => 0,
+            Self::LostSightOfPlayer => 1,
+            Self::PropThrown => 2,
+        }
+    }
+
+    /// How long an NPC waits before barking about the same thing again.
+    fn cooldown(self) -> Duration {
+        match self {
+            Self::PlayerApproached => Duration::from_secs(30),
+            Self::PropThrown => Duration::from_secs(10),
+            Self::LostSightOfPlayer => Duration::from_secs(20),
+        }
+    }
+}
+
+/// Makes an NPC bark, unless it is on cooldown or already saying something more important.
+#[derive(EntityEvent, Debug)]
+pub(crate) struct Bark {
+    pub(crate) entity: Entity,
+    pub(crate) kind: BarkKind,
+}
+
+/// An NPC that barks. Keeps track of what it already said and what it knows about the player.
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub(crate) struct Barker {
+    /// When each kind of bark was last said, in seconds since the game started.
+    last_said: HashMap<BarkKind, f32>,
+    current: Option<CurrentBark>,
+    player_nearby: bool,
+    saw_player: bool,
+    /// How long the player has been out of sight.
+    unseen_secs: f32,
+}
+
+#[derive(Debug, Reflect)]
+struct CurrentBark {
+    kind: BarkKind,
+    subtitle: Entity,
+    timer: Timer,
+}
+
+/// The floating text of a bark, placed above the NPC that says it.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct BarkSubtitle {
+    npc: Entity,
+}
+
+/// NPCs don't start another bark this soon after the last one, unless it interrupts the current one.
+const MIN_BARK_INTERVAL: f32 = 4.0;
+/// How long a bark stays on screen.
+const BARK_DURATION: Duration = Duration::from_secs(3);
+const BARK_SUBTITLE_WIDTH: f32 = 300.0;
+/// How far above the NPC's center the subtitle floats.
+const BARK_SUBTITLE_HEIGHT: f32 = NPC_HALF_HEIGHT + 0.4;
+
+const APPROACH_DISTANCE: f32 = 5.0;
+/// The player needs to get this far away again before approaching counts again.
+const LEAVE_DISTANCE: f32 = 8.0;
+const THROW_NOTICE_DISTANCE: f32 = 10.0;
+const SIGHT_DISTANCE: f32 = 20.0;
+/// How long the player needs to stay out of sight before the NPC notices.
+const LOST_SIGHT_DELAY: f32 = 1.0;
+
+fn play_bark(
+    bark: On<Bark>,
+    mut barkers: Query<&mut Barker, Without<InConversation>>,
+    yarn_project: Option<Res<YarnProject>>,
+    dialogue_runner: Option<Single<&DialogueRunner>>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    let Ok(mut barker) = barkers.get_mut(bark.entity) else {
+        return;
+    };
+    let now = time.elapsed_secs();
+    match &barker.current {
+        Some(current) if current.kind.priority() >= bark.kind.priority() => return,
+        Some(_) => {}
+        None => {
+            let last_bark = barker.last_said.values().copied().reduce(f32::max);
+            if last_bark.is_some_and(|last_bark| now - last_bark < MIN_BARK_INTERVAL) {
+                return;
+            }
+        }
+    }
+    if barker
+        .last_said
+        .get(&bark.kind)
+        .is_some_and(|last_said| now - last_said < bark.kind.cooldown().as_secs_f32())
+    {
+        return;
+    }
+    let line = yarn_project
+        .zip(dialogue_runner)
+        .and_then(|(project, dialogue_runner)| {
+            random_line(&project, dialogue_runner.text_provider(), bark.kind.node())
+        });
+    let Some(line) = line else {
+        warn!("There are no lines for the Yarn node {}", bark.kind.node());
+        return;
+    };
+
+    if let Some(current) = barker.current.take() {
+        commands.entity(current.subtitle).try_despawn();
+    }
+    let subtitle = commands
+        .spawn((
+            Name::new("Bark Subtitle"),
+            BarkSubtitle { npc: bark.entity },
+            Node {
+                position_type: PositionType::Absolute,
+                width: Px(BARK_SUBTITLE_WIDTH),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            // Only shown once it is placed above the NPC.
+            Visibility::Hidden,
+            DespawnOnExit(Screen::Gameplay),
+            Pickable::IGNORE,
+            children![(
+                Text(line),
+                TextFont::from_font_size(20.0),
+                TextShadow::default(),
+            )],
+        ))
+        .id();
+    barker.last_said.insert(bark.kind, now);
+    barker.current = Some(CurrentBark {
+        kind: bark.kind,
+        subtitle,
+        timer: Timer::new(BARK_DURATION, TimerMode::Once),
+    });
+}
+
+/// Picks one of the lines of a Yarn node without running it.
+/// The text comes from the dialogue's text provider, so barks use the same localization as conversations.
+fn random_line(
+    project: &YarnProject,
+    text_provider: &dyn TextProvider,
+    node: &str,
+) -> Option<String> {
+    let lines: Vec<_> = project
+        .compilation()
+        .string_table
+        .iter()
+        .filter(|(_, info)| info.node_name == node)
+        .map(|(id, _)| id)
+        .collect();
+    text_provider.get_text(lines.choose(&mut rand::rng())?)
+}
+
+fn interrupt_bark_for_conversation(
+    add: On<Add, InConversation>,
+    mut barkers: Query<&mut Barker>,
+    mut commands: Commands,
+) {
+    let Ok(mut barker) = barkers.get_mut(add.entity) else {
+        return;
+    };
+    if let Some(current) = barker.current.take() {
+        commands.entity(current.subtitle).try_despawn();
+    }
+}
+
+fn bark_when_player_approaches(
+    mut barkers: Query<(Entity, &GlobalTransform, &mut Barker)>,
+    player: Single<&GlobalTransform, With<Player>>,
+    mut commands: Commands,
+) {
+    for (npc, transform, mut barker) in &mut barkers {
+        let distance = transform.translation().distance(player.translation());
+        if !barker.player_nearby && distance < APPROACH_DISTANCE {
+            barker.player_nearby = true;
+            commands.trigger(Bark {
+                entity: npc,
+                kind: BarkKind::PlayerApproached,
+            });
+        } else if barker.player_nearby && distance > LEAVE_DISTANCE {
+            barker.player_nearby = false;
+        }
+    }
+}
+
+fn bark_when_prop_thrown(
+    mut thrown: MessageReader<PropThrown>,
+    barkers: Query<(Entity, &GlobalTransform), With<Barker>>,
+    props: Query<&GlobalTransform>,
+    mut commands: Commands,
+) {
+    for prop in thrown
+        .read()
+        .filter_map(|thrown| props.get(thrown.prop).ok())
+    {
+        for (npc, transform) in &barkers {
+            if transform.translation().distance(prop.translation()) < THROW_NOTICE_DISTANCE {
+                commands.trigger(Bark {
+                    entity: npc,
+                    kind: BarkKind::PropThrown,
+                });
+            }
+        }
+    }
+}
+
+fn bark_when_losing_sight_of_player(
+    mut barkers: Query<(Entity, &GlobalTransform, &mut Barker)>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    spatial_query: SpatialQuery,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (npc, transform, mut barker) in &mut barkers {
+        let eyes = transform.translation() + Vec3::Y * NPC_HALF_HEIGHT;
+        let to_player = camera.translation() - eyes;
+        let distance = to_player.length();
+        let Ok(direction) = Dir3::new(to_player) else {
+            continue;
+        };
+        // Only the level geometry blocks the view, not props or characters.
+        let sees_player = distance < SIGHT_DISTANCE
+            && spatial_query
+                .cast_ray(
+                    eyes,
+                    direction,
+                    distance,
+                    true,
+                    &SpatialQueryFilter::from_mask(CollisionLayer::Default),
+                )
+                .is_none();
+        if sees_player {
+            barker.saw_player = true;
+            barker.unseen_secs = 0.0;
+            continue;
+        }
+        barker.unseen_secs += time.delta_secs();
+        if barker.saw_player && barker.unseen_secs >= LOST_SIGHT_DELAY {
+            barker.saw_player = false;
+            commands.trigger(Bark {
+                entity: npc,
+                kind: BarkKind::LostSightOfPlayer,
+            });
+        }
+    }
+}
+
+fn finish_barks(mut barkers: Query<&mut Barker>, time: Res<Time>, mut commands: Commands) {
+    for mut barker in &mut barkers {
+        let Some(current) = &mut barker.current else {
+            continue;
+        };
+        current.timer.tick(time.delta());
+        if current.timer.is_finished() {
+            commands.entity(current.subtitle).try_despawn();
+            barker.current = None;
+        }
+    }
+}
+
+/// Projects the position above each barking NPC onto the screen.
+fn place_bark_subtitles(
+    mut subtitles: Query<(Entity, &BarkSubtitle, &mut Node, &mut Visibility)>,
+    npcs: Query<&GlobalTransform>,
+    camera: Single<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+    mut commands: Commands,
+) {
+    let (camera, camera_transform) = camera.into_inner();
+    for (subtitle, bark, mut node, mut visibility) in &mut subtitles {
+        let Ok(npc_transform) = npcs.get(bark.npc) else {
+            commands.entity(subtitle).despawn();
+            continue;
+        };
+        let anchor = npc_transform.translation() + Vec3::Y * BARK_SUBTITLE_HEIGHT;
+        // Fails when the NPC is behind the camera.
+        let Ok(position) = camera.world_to_viewport(camera_transform, anchor) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        node.left = Px(position.x - BARK_SUBTITLE_WIDTH / 2.0);
+        node.top = Px(position.y);
+        *visibility = Visibility::Inherited;
+    }
+}
@@ -0,0 +1,184 @@
+//! NPCs stop, turn towards the player and look at them while the player is talking to them.
+
+use std::f32::consts::FRAC_PI_6;
+
+use bevy::{animation::AnimationSystems, prelude::*, transform::TransformSystems};
+use bevy_landmass::prelude::*;
+
+use crate::{
+    PostPhysicsAppSystems,
+    gameplay::player::{camera::PlayerCamera, dialogue::InConversation},
+    screens::Screen,
+};
+
+use super::{
+    Npc,
+    ai::{Agent, WantsToFollowPlayer},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(stop_agent);
+    app.add_observer(resume_agent);
+    app.add_systems(
+        Update,
+        face_player
+            .in_set(PostPhysicsAppSystems::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        PostUpdate,
+        (find_head_bones, look_at_player)
+            .chain()
+            .after(AnimationSystems)
+            .before(TransformSystems::Propagate)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// The name of the head bone in the fox's skeleton.
+const HEAD_BONE: &str = "b_Head_05";
+/// How far the head can turn to the sides.
+const MAX_HEAD_YAW: f32 = 70.0_f32.to_radians();
+/// How far the head can tilt up and down.
+const MAX_HEAD_PITCH: f32 = FRAC_PI_6;
+
+/// Rotates the head bone of an NPC towards the player camera, on top of the animated pose.
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub(crate) struct HeadLookAt {
+    /// The head bone of the NPC's model, found once the model has been spawned.
+    bone: Option<Entity>,
+    /// How much the look-at overrides the animation. Fades in during conversations and out afterwards.
+    weight: f32,
+}
+
+/// Added to an agent whose NPC was following the player before the conversation started.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct FollowPlayerAfterConversation;
+
+fn stop_agent(
+    add: On<Add, InConversation>,
+    npcs: Query<&Agent, With<Npc>>,
+    mut agents: Query<(&mut AgentTarget3d, Has<WantsToFollowPlayer>)>,
+    mut commands: Commands,
+) {
+    let Ok(agent) = npcs.get(add.entity) else {
+        return;
+    };
+    let Ok((mut target, follows_player)) = agents.get_mut(**agent) else {
+        return;
+    };
+    *target = AgentTarget3d::None;
+    if follows_player {
+        commands
+            .entity(**agent)
+            .remove::<WantsToFollowPlayer>()
+            .insert(FollowPlayerAfterConversation);
+    }
+}
+
+fn resume_agent(
+    remove: On<Remove, InConversation>,
+    npcs: Query<&Agent, With<Npc>>,
+    agents: Query<(), With<FollowPlayerAfterConversation>>,
+    mut commands: Commands,
+) {
+    let Ok(agent) = npcs.get(remove.entity) else {
+        return;
+    };
+    if agents.contains(**agent) {
+        // The NPC might be removed together with its agent, e.g. when leaving the level.
+        commands
+            .entity(**agent)
+            .try_remove::<FollowPlayerAfterConversation>()
+            .try_insert(WantsToFollowPlayer);
+    }
+}
+
+/// Turns the whole NPC towards the player, since it is not moving and thus not turned by its velocity.
+fn face_player(
+    mut npcs: Query<&mut Transform, (With<Npc>, With<InConversation>)>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    time: Res<Time>,
+) {
+    for mut transform in &mut npcs {
+        let to_player = (camera.translation() - transform.translation).with_y(0.0);
+        let Ok(direction) = Dir3::new(to_player) else {
+            continue;
+        };
+        let target = transform.looking_to(direction, Vec3::Y).rotation;
+        let decay_rate = f32::ln(100.0);
+        transform
+            .rotation
+            .smooth_nudge(&target, decay_rate, time.delta_secs());
+    }
+}
+
+fn find_head_bones(
+    mut npcs: Query<(Entity, &mut HeadLookAt)>,
+    children: Query<&Children>,
+    names: Query<&Name>,
+) {
+    for (npc, mut look_at) in &mut npcs {
+        if look_at.bone.is_some() {
+            continue;
+        }
+        look_at.bone = children.iter_descendants(npc).find(|entity| {
+            names
+                .get(*entity)
+                .is_ok_and(|name| name.as_str() == HEAD_BONE)
+        });
+    }
+}
+
+/// Rotates the head bones after the animations were applied, but before the transforms are propagated.
+/// Since the global transforms are still the ones of the last frame at this point, the rotation lags behind by a frame,
+/// which is not noticeable.
+fn look_at_player(
+    mut npcs: Query<(&GlobalTransform, &mut HeadLookAt, Has<InConversation>)>,
+    mut bones: Query<(&mut Transform, &ChildOf)>,
+    global_transforms: Query<&GlobalTransform>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    time: Res<Time>,
+) {
+    for (npc_transform, mut look_at, in_conversation) in &mut npcs {
+        let target_weight = if in_conversation { 1.0 } else { 0.0 };
+        let decay_rate = f32::ln(50.0);
+        look_at
+            .weight
+            .smooth_nudge(&target_weight, decay_rate, time.delta_secs());
+        let Some(bone) = look_at.bone else {
+            continue;
+        };
+        if look_at.weight < 0.001 {
+            continue;
+        }
+        let Ok((mut bone_transform, parent)) = bones.get_mut(bone) else {
+            continue;
+        };
+        let (Ok(parent_global), Ok(bone_global)) = (
+            global_transforms.get(parent.parent()),
+            global_transforms.get(bone),
+        ) else {
+            continue;
+        };
+
+        // Find out where the player is relative to the direction the NPC is facing, and clamp that to the joint limits.
+        let npc_rotation = npc_transform.rotation();
+        let to_camera = npc_rotation.inverse() * (camera.translation() - bone_global.translation());
+        let Ok(to_camera) = Dir3::new(to_camera) else {
+            continue;
+        };
+        let yaw = f32::atan2(-to_camera.x, -to_camera.z).clamp(-MAX_HEAD_YAW, MAX_HEAD_YAW);
+        let pitch = to_camera.y.asin().clamp(-MAX_HEAD_PITCH, MAX_HEAD_PITCH);
+        let local_offset = Quat::from_rotation_y(yaw) * Quat::from_rotation_x(pitch);
+        let world_offset = npc_rotation * local_offset * npc_rotation.inverse();
+        let world_offset = Quat::IDENTITY.slerp(world_offset, look_at.weight);
+
+        // Apply the offset on top of the animated rotation of the head in world space, then convert it back to local space.
+        let parent_rotation = parent_global.rotation();
+        let animated_rotation = parent_rotation * bone_transform.rotation;
+        bone_transform.rotation = parent_rotation.inverse() * world_offset * animated_rotation;
+    }
+}
@@ -1,6 +1,5 @@
 //! NPC handling. In the demo, the NPC is a fox that moves towards the player. We can interact with the NPC to trigger dialogue.
 
-use animation::{NpcAnimationState, setup_npc_animations};
 use avian3d::prelude::*;
 use bevy::prelude::*;
 
@@ -8,23 +7,38 @@ use bevy_ahoy::CharacterController;
 use bevy_trenchbroom::prelude::*;
 
 use crate::{
-    animation::AnimationState,
+    animation::state_machine::AnimationController,
     asset_tracking::LoadResource,
     third_party::{
         avian3d::CollisionLayer,
         bevy_trenchbroom::{GetTrenchbroomModelPath, LoadTrenchbroomModel as _},
-        bevy_yarnspinner::YarnNode,
+        bevy_yarnspinner::{DialogueShot, YarnNode},
     },
 };
 
 use super::animation::AnimationPlayerAncestor;
+use animation::PreviousYaw;
+use assets::NPC_ANIMATIONS;
+use bark::Barker;
+use conversation::HeadLookAt;
 pub(crate) mod ai;
 mod animation;
 mod assets;
+pub(crate) mod bark;
+mod conversation;
+pub(crate) mod nav_link;
 mod sound;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((ai::plugin, animation::plugin, assets::plugin, sound::plugin));
+    app.add_plugins((
+        ai::plugin,
+        animation::plugin,
+        assets::plugin,
+        bark::plugin,
+        conversation::plugin,
+        nav_link::plugin,
+        sound::plugin,
+    ));
     app.load_asset::<Gltf>(Npc::model_path());
     app.add_observer(on_add);
 }
@@ -50,16 +64,21 @@ fn on_add(add: On<Add, Npc>, mut commands: Commands, assets: Res<AssetServer>) {
             },
             ColliderDensity(1_000.0),
             RigidBody::Kinematic,
-            AnimationState::<NpcAnimationState>::default(),
             AnimationPlayerAncestor,
+            AnimationController::new(assets.load(NPC_ANIMATIONS)),
+            PreviousYaw::default(),
+            HeadLookAt::default(),
+            Barker::default(),
             CollisionLayers::new(CollisionLayer::Character, LayerMask::ALL),
             // The Yarn Node is what we use to trigger dialogue.
-            YarnNode::new("Npc"),
+            YarnNode {
+                shot: DialogueShot::CloseUp,
+                ..YarnNode::new("Npc")
+            },
         ))
         .with_child((
             Name::new("Npc Model"),
             SceneRoot(assets.load_trenchbroom_model::<Npc>()),
             Transform::from_xyz(0.0, -NPC_FLOAT_HEIGHT, 0.0),
-        ))
-        .observe(setup_npc_animations);
+        ));
 }
@@ -0,0 +1,196 @@
+//! Off-mesh links let NPCs leave the navmesh to jump over gaps, drop down ledges or climb up onto them.
+//!
+//! A link is authored in TrenchBroom as a `nav_link` placed where the NPC starts, with its `target` set to the
+//! `targetname` of a `nav_link_end` placed where the NPC lands. The link is registered with landmass as an
+//! animation link, so paths can use it to connect otherwise unconnected parts of the navmesh.
+//! When an agent reaches a link, we take over steering and mock the matching movement inputs (see `ai.rs`) until the
+//! NPC arrives at the end of the link, after which landmass steers again.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_landmass::prelude::*;
+use bevy_trenchbroom::prelude::*;
+
+use crate::screens::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(queue_nav_link_registration);
+    app.add_systems(
+        Update,
+        register_nav_links.run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// The start of an off-mesh link. See the module documentation for how to set one up.
+#[point_class(base(Transform, Visibility), classname("nav_link"))]
+pub(crate) struct NavLink {
+    /// The `targetname` of the [`NavLinkEnd`] this link leads to.
+    #[class(must_set)]
+    pub(crate) target: String,
+    pub(crate) kind: NavLinkKind,
+    /// How wide the entry and exit of the link are, in meters.
+    pub(crate) width: f32,
+    /// Whether NPCs can also use the link from the end to the start.
+    pub(crate) bidirectional: bool,
+}
+
+impl Default for NavLink {
+    fn default() -> Self {
+        Self {
+            target: String::new(),
+            kind: NavLinkKind::default(),
+            width: 1.0,
+            bidirectional: false,
+        }
+    }
+}
+
+/// The end of an off-mesh link.
+#[point_class(base(Transform, Visibility), classname("nav_link_end"))]
+#[derive(Default)]
+pub(crate) struct NavLinkEnd {
+    #[class(must_set)]
+    pub(crate) targetname: String,
+}
+
+/// How the NPC gets from the start to the end of a [`NavLink`].
+#[derive(FgdType, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NavLinkKind {
+    /// Jump over a gap.
+    #[default]
+    Jump,
+    /// Walk off a ledge.
+    Drop,
+    /// Mantle up onto a ledge.
+    Climb,
+}
+
+impl NavLinkKind {
+    const ALL: [Self; 3] = [Self::Jump, Self::Drop, Self::Climb];
+
+    /// The kind landmass uses to refer to this link.
+    fn landmass_kind(self) -> usize {
+        self as usize
+    }
+
+    fn from_landmass_kind(kind: usize) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|link| link.landmass_kind() == kind)
+    }
+
+    /// How costly using the link is compared to walking the same distance.
+    fn cost(self) -> f32 {
+        match self {
+            Self::Jump => 2.0,
+            Self::Drop => 1.5,
+            Self::Climb => 3.0,
+        }
+    }
+}
+
+/// Added to an NPC while it is traversing a [`NavLink`]. Landmass does not steer the NPC in the meantime.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub(crate) struct TraversingNavLink {
+    kind: NavLinkKind,
+    end: Vec3,
+    timer: Timer,
+    jumped: bool,
+}
+
+/// The inputs an NPC needs to traverse its [`NavLink`] this frame.
+pub(super) struct NavLinkInputs {
+    pub(super) direction: Option<Dir3>,
+    pub(super) jump: bool,
+    pub(super) mantle: bool,
+}
+
+impl TraversingNavLink {
+    /// Starts traversing a link that an agent reached, if it is one of ours.
+    pub(super) fn start(link: &AnimationLink3d, end: Vec3) -> Option<Self> {
+        Some(Self {
+            kind: NavLinkKind::from_landmass_kind(link.kind)?,
+            end,
+            timer: Timer::new(MAX_TRAVERSAL_DURATION, TimerMode::Once),
+            jumped: false,
+        })
+    }
+
+    /// Steers the NPC with its feet at `feet` towards the end of the link.
+    /// Returns `None` once the NPC arrived or gave up.
+    pub(super) fn advance(&mut self, delta: Duration, feet: Vec3) -> Option<NavLinkInputs> {
+        self.timer.tick(delta);
+        let to_end = self.end - feet;
+        let arrived = to_end.with_y(0.0).length() < ARRIVAL_DISTANCE && to_end.y.abs() < 1.0;
+        if arrived {
+            return None;
+        }
+        if self.timer.is_finished() {
+            warn!("An NPC gave up on traversing a {:?} nav link", self.kind);
+            return None;
+        }
+        let inputs = NavLinkInputs {
+            direction: Dir3::new(to_end.with_y(0.0)).ok(),
+            // Jumps are a single press at the start of the link.
+            jump: self.kind == NavLinkKind::Jump && !self.jumped,
+            // Mantling needs the input to be held until the NPC is up.
+            mantle: self.kind == NavLinkKind::Climb,
+        };
+        self.jumped |= inputs.jump;
+        Some(inputs)
+    }
+}
+
+/// Give up on a link after this long, e.g. when a prop is in the way.
+const MAX_TRAVERSAL_DURATION: Duration = Duration::from_secs(4);
+/// How close the NPC needs to get to the end of a link to be done with it.
+const ARRIVAL_DISTANCE: f32 = 0.5;
+
+/// Added to a [`NavLink`] until it is registered with landmass.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct UnregisteredNavLink;
+
+/// Links are registered once their transforms are propagated, which happens after the level's scene was spawned.
+/// This also makes sure that the [`NavLinkEnd`] was spawned, no matter in which order the map lists them.
+fn queue_nav_link_registration(add: On<Add, NavLink>, mut commands: Commands) {
+    commands.entity(add.entity).insert(UnregisteredNavLink);
+}
+
+fn register_nav_links(
+    mut commands: Commands,
+    links: Query<(Entity, &NavLink, &GlobalTransform), With<UnregisteredNavLink>>,
+    ends: Query<(&NavLinkEnd, &GlobalTransform)>,
+    archipelago: Single<Entity, With<Archipelago3d>>,
+) {
+    for (entity, link, start) in &links {
+        commands.entity(entity).remove::<UnregisteredNavLink>();
+        let Some((_, end)) = ends.iter().find(|(end, _)| end.targetname == link.target) else {
+            error!(
+                "The nav link {entity} targets \"{}\", but there is no `nav_link_end` with that `targetname`",
+                link.target
+            );
+            continue;
+        };
+        let start = start.translation();
+        let end = end.translation();
+        // The edges are perpendicular to the direction of the link, so that agents can enter it anywhere along its width.
+        let Ok(direction) = Dir3::new((end - start).with_y(0.0)) else {
+            error!("The nav link {entity} starts and ends at the same horizontal position");
+            continue;
+        };
+        let half_width = direction.cross(Vec3::Y) * link.width / 2.0;
+        commands.entity(entity).insert(AnimationLink3dBundle {
+            link: AnimationLink3d {
+                start_edge: (start - half_width, start + half_width),
+                end_edge: (end - half_width, end + half_width),
+                kind: link.kind.landmass_kind(),
+                cost: link.kind.cost(),
+                bidirectional: link.bidirectional,
+            },
+            archipelago_ref: ArchipelagoRef3d::new(*archipelago),
+        });
+    }
+}
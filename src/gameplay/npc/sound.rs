@@ -1,7 +1,11 @@
 //! NPC sound handling. The only sound is a step sound that plays when the NPC is walking.
 
 use super::{Npc, assets::NpcAssets};
-use crate::{PostPhysicsAppSystems, audio::SpatialPool, screens::Screen};
+use crate::{
+    PostPhysicsAppSystems,
+    audio::{SpatialPool, caption::Caption},
+    screens::Screen,
+};
 use avian3d::prelude::LinearVelocity;
 use bevy::prelude::*;
 use bevy_ahoy::CharacterControllerState;
@@ -57,5 +61,6 @@ fn play_step_sound(
             ..default()
         },
         SpatialPool,
+        Caption::new("[footsteps]"),
     ));
 }
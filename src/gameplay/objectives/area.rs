@@ -0,0 +1,44 @@
+//! Invisible brushes that report [`ObjectiveCondition::AreaEntered`] when the player walks into them.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_trenchbroom::prelude::*;
+
+use crate::gameplay::player::Player;
+
+use super::{ObjectiveCondition, ObjectiveConditionMet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(setup_objective_area);
+}
+
+#[solid_class(base(Transform, Visibility))]
+#[derive(Default)]
+pub(crate) struct ObjectiveArea {
+    #[class(must_set)]
+    pub(crate) targetname: String,
+}
+
+fn setup_objective_area(add: On<Add, ObjectiveArea>, mut commands: Commands) {
+    commands
+        .entity(add.entity)
+        .insert((Sensor, CollisionEventsEnabled, Visibility::Hidden))
+        .observe(report_entered_area);
+}
+
+fn report_entered_area(
+    collision: On<CollisionStart>,
+    areas: Query<&ObjectiveArea>,
+    players: Query<(), With<Player>>,
+    mut commands: Commands,
+) {
+    if !collision.body2.is_some_and(|body| players.contains(body)) {
+        return;
+    }
+    let Ok(area) = areas.get(collision.collider1) else {
+        return;
+    };
+    commands.trigger(ObjectiveConditionMet(ObjectiveCondition::AreaEntered(
+        area.targetname.clone(),
+    )));
+}
@@ -0,0 +1,157 @@
+//! Lists the active objectives in the top right corner while playing, and announces changes to them.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, ui::Val::*};
+
+use crate::{
+    PostPhysicsAppSystems,
+    screens::Screen,
+    theme::palette::{HEADER_TEXT, LABEL_TEXT},
+};
+
+use super::{ObjectiveState, ObjectiveStateChanged, Objectives};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_objective_tracker);
+    app.add_systems(
+        Update,
+        update_objective_tracker
+            .run_if(in_state(Screen::Gameplay).and(resource_changed::<Objectives>))
+            .in_set(PostPhysicsAppSystems::ChangeUi),
+    );
+    app.add_observer(announce_objective_change);
+    app.add_systems(
+        Update,
+        remove_expired_announcements
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PostPhysicsAppSystems::TickTimers),
+    );
+}
+
+/// How long an announcement of a changed objective stays on screen.
+const ANNOUNCEMENT_DURATION: Duration = Duration::from_secs(4);
+
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct ObjectiveTracker;
+
+fn spawn_objective_tracker(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Objective Tracker"),
+        ObjectiveTracker,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Px(20.0),
+            right: Px(20.0),
+            max_width: Px(400.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::End,
+            row_gap: Px(6.0),
+            ..default()
+        },
+        DespawnOnExit(Screen::Gameplay),
+        Pickable::IGNORE,
+    ));
+}
+
+fn update_objective_tracker(
+    mut commands: Commands,
+    tracker: Single<Entity, With<ObjectiveTracker>>,
+    objectives: Res<Objectives>,
+) {
+    let active: Vec<_> = objectives
+        .iter()
+        .filter(|objective| objective.state == ObjectiveState::Active && objective.is_revealed())
+        .map(|objective| {
+            if objective.is_optional() {
+                format!("{} (optional)", objective.title())
+            } else {
+                objective.title().to_string()
+            }
+        })
+        .collect();
+
+    commands
+        .entity(*tracker)
+        .despawn_related::<Children>()
+        .with_children(|parent| {
+            if active.is_empty() {
+                return;
+            }
+            parent.spawn((
+                Text::new("Objectives"),
+                TextFont::from_font_size(22.0),
+                TextColor(HEADER_TEXT),
+                TextShadow::default(),
+            ));
+            for title in active {
+                parent.spawn((
+                    Text(title),
+                    TextFont::from_font_size(18.0),
+                    TextColor(LABEL_TEXT),
+                    TextShadow::default(),
+                ));
+            }
+        });
+}
+
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct ObjectiveAnnouncement(Timer);
+
+fn announce_objective_change(
+    changed: On<ObjectiveStateChanged>,
+    mut commands: Commands,
+    objectives: Res<Objectives>,
+    announcements: Query<Entity, With<ObjectiveAnnouncement>>,
+) {
+    let Some(objective) = objectives.get(&changed.id) else {
+        return;
+    };
+    if !objective.is_revealed() {
+        return;
+    }
+    let text = match changed.state {
+        ObjectiveState::Inactive => return,
+        ObjectiveState::Active => format!("New objective: {}", objective.title()),
+        ObjectiveState::Complete => format!("Objective complete: {}", objective.title()),
+        ObjectiveState::Failed => format!("Objective failed: {}", objective.title()),
+    };
+    // Only the latest change is announced.
+    for announcement in &announcements {
+        commands.entity(announcement).despawn();
+    }
+    commands.spawn((
+        Name::new("Objective Announcement"),
+        ObjectiveAnnouncement(Timer::new(ANNOUNCEMENT_DURATION, TimerMode::Once)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Percent(20.0),
+            width: Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        DespawnOnExit(Screen::Gameplay),
+        Pickable::IGNORE,
+        children![(
+            Text(text),
+            TextFont::from_font_size(28.0),
+            TextColor(HEADER_TEXT),
+            TextShadow::default(),
+        )],
+    ));
+}
+
+fn remove_expired_announcements(
+    mut commands: Commands,
+    mut announcements: Query<(Entity, &mut ObjectiveAnnouncement)>,
+    time: Res<Time>,
+) {
+    for (entity, mut announcement) in &mut announcements {
+        announcement.0.tick(time.delta());
+        if announcement.0.is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
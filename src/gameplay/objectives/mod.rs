@@ -0,0 +1,525 @@
+//! Objectives give the level a mission structure.
+//!
+//! The objectives of a level are defined in a `.mission.ron` asset next to its map:
+//!
+//! ```ron
+//! (
+//!     objectives: [
+//!         (
+//!             id: "meet",
+//!             title: "Talk to the Follower",
+//!             active: true,
+//!             complete_when: [DialogueNodeVisited("Npc")],
+//!             activates: ["features"],
+//!         ),
+//!         (
+//!             id: "features",
+//!             title: "Ask about Foxtrot's features",
+//!             optional: true,
+//!             complete_when: [DialogueNodeVisited("Features")],
+//!         ),
+//!     ],
+//! )
+//! ```
+//!
+//! Objectives start out inactive unless `active` is set, and are activated when an objective listing them in
+//! `activates` is completed. An active objective is complete once all of its `complete_when` conditions were met,
+//! and fails as soon as one of its `fail_when` conditions is met. Hidden objectives are only shown once complete.
+//!
+//! Other systems report conditions by triggering [`ObjectiveConditionMet`], or change the state of an objective
+//! directly by triggering [`SetObjectiveState`]. Every change is announced with [`ObjectiveStateChanged`].
+
+use avian_pickup::prop::HeldProp;
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    platform::collections::HashSet,
+    prelude::*,
+};
+use bevy_yarnspinner::events::NodeStarted;
+use serde::Deserialize;
+
+use crate::{
+    gameplay::{level::LevelAssets, player::interaction::Interacted},
+    screens::Screen,
+};
+
+mod area;
+mod hud;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<Mission>();
+    app.init_asset_loader::<MissionLoader>();
+    app.init_resource::<Objectives>();
+    app.add_systems(OnEnter(Screen::Gameplay), start_mission);
+    app.add_observer(meet_condition);
+    app.add_observer(set_objective_state);
+    app.add_observer(report_visited_dialogue_nodes);
+    app.add_observer(report_used_entities);
+    app.add_observer(report_collected_items);
+
+    app.add_plugins((area::plugin, hud::plugin));
+}
+
+/// Something that happened in the world that objectives can wait for.
+#[derive(Deserialize, Reflect, Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ObjectiveCondition {
+    /// The player picked up the prop with this [`Name`]. Props from `generic.props.ron` are named after their classname.
+    ItemCollected(String),
+    /// The player entered the [`ObjectiveArea`](area::ObjectiveArea) with this `targetname`.
+    AreaEntered(String),
+    /// The player interacted with the entity with this [`Name`].
+    EntityUsed(String),
+    /// The Yarn node with this title was started.
+    DialogueNodeVisited(String),
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum ObjectiveState {
+    #[default]
+    Inactive,
+    Active,
+    Complete,
+    Failed,
+}
+
+/// Reports that a condition was met. Completes or fails the active objectives waiting for it.
+#[derive(Event, Debug, Clone)]
+pub(crate) struct ObjectiveConditionMet(pub(crate) ObjectiveCondition);
+
+/// Sets the state of an objective regardless of its conditions.
+#[derive(Event, Debug, Clone)]
+pub(crate) struct SetObjectiveState {
+    pub(crate) id: String,
+    pub(crate) state: ObjectiveState,
+}
+
+/// Triggered after the state of an objective changed.
+#[derive(Event, Debug, Clone)]
+pub(crate) struct ObjectiveStateChanged {
+    pub(crate) id: String,
+    pub(crate) state: ObjectiveState,
+}
+
+/// The objectives of a level, as loaded from a `.mission.ron` file.
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub(crate) struct Mission {
+    objectives: Vec<ObjectiveDefinition>,
+}
+
+impl Mission {
+    /// Parses a mission and checks that its objective ids are unique and every activated objective exists.
+    fn from_ron(ron: &str) -> Result<Self, String> {
+        let mission: Self = ron::from_str(ron).map_err(|error| error.to_string())?;
+        let mut ids = HashSet::new();
+        for objective in &mission.objectives {
+            if !ids.insert(objective.id.as_str()) {
+                return Err(format!(
+                    "The objective id \"{}\" is used twice",
+                    objective.id
+                ));
+            }
+        }
+        for objective in &mission.objectives {
+            if let Some(next) = objective
+                .activates
+                .iter()
+                .find(|next| !ids.contains(next.as_str()))
+            {
+                return Err(format!(
+                    "The objective \"{}\" activates \"{next}\", but there is no objective with that id",
+                    objective.id
+                ));
+            }
+        }
+        Ok(mission)
+    }
+}
+
+#[derive(Deserialize, Reflect, Debug, Clone)]
+struct ObjectiveDefinition {
+    id: String,
+    title: String,
+    #[serde(default)]
+    description: String,
+    /// Whether the objective is active from the start.
+    #[serde(default)]
+    active: bool,
+    #[serde(default)]
+    optional: bool,
+    #[serde(default)]
+    hidden: bool,
+    #[serde(default)]
+    complete_when: Vec<ObjectiveCondition>,
+    #[serde(default)]
+    fail_when: Vec<ObjectiveCondition>,
+    /// The objectives that become active once this one is complete.
+    #[serde(default)]
+    activates: Vec<String>,
+}
+
+/// The objectives of the current level and how far along the player is with them.
+#[derive(Resource, Debug, Default, Reflect)]
+#[reflect(Resource)]
+pub(crate) struct Objectives(Vec<Objective>);
+
+impl Objectives {
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Objective> {
+        self.0.iter()
+    }
+
+    pub(crate) fn get(&self, id: &str) -> Option<&Objective> {
+        self.0
+            .iter()
+            .find(|objective| objective.definition.id == id)
+    }
+
+    fn get_mut(&mut self, id: &str) -> Option<&mut Objective> {
+        self.0
+            .iter_mut()
+            .find(|objective| objective.definition.id == id)
+    }
+
+    /// Changes the state of an objective and activates the objectives that follow it.
+    /// Returns every change that was made, in order.
+    fn transition(&mut self, id: &str, state: ObjectiveState) -> Vec<ObjectiveStateChanged> {
+        let mut changes = Vec::new();
+        self.transition_into(id, state, &mut changes);
+        changes
+    }
+
+    fn transition_into(
+        &mut self,
+        id: &str,
+        state: ObjectiveState,
+        changes: &mut Vec<ObjectiveStateChanged>,
+    ) {
+        let Some(objective) = self.get_mut(id) else {
+            warn!("There is no objective with the id \"{id}\"");
+            return;
+        };
+        if objective.state == state {
+            return;
+        }
+        objective.state = state;
+        changes.push(ObjectiveStateChanged {
+            id: id.to_string(),
+            state,
+        });
+        if state != ObjectiveState::Complete {
+            return;
+        }
+        for next in objective.definition.activates.clone() {
+            if self
+                .get_mut(&next)
+                .is_some_and(|next| next.state == ObjectiveState::Inactive)
+            {
+                self.transition_into(&next, ObjectiveState::Active, changes);
+            }
+        }
+    }
+
+    /// Completes or fails the active objectives waiting for `condition`. Returns every change that was made, in order.
+    fn meet(&mut self, condition: &ObjectiveCondition) -> Vec<ObjectiveStateChanged> {
+        let mut completed = Vec::new();
+        let mut failed = Vec::new();
+        for objective in &mut self.0 {
+            if objective.state != ObjectiveState::Active {
+                continue;
+            }
+            if objective.definition.fail_when.contains(condition) {
+                failed.push(objective.definition.id.clone());
+                continue;
+            }
+            for (expected, met) in objective
+                .definition
+                .complete_when
+                .iter()
+                .zip(&mut objective.met)
+            {
+                *met |= expected == condition;
+            }
+            if !objective.met.is_empty() && objective.met.iter().all(|met| *met) {
+                completed.push(objective.definition.id.clone());
+            }
+        }
+        let mut changes = Vec::new();
+        for id in failed {
+            self.transition_into(&id, ObjectiveState::Failed, &mut changes);
+        }
+        for id in completed {
+            self.transition_into(&id, ObjectiveState::Complete, &mut changes);
+        }
+        changes
+    }
+
+    /// Starts a mission with all of its objectives inactive.
+    fn from_mission(mission: &Mission) -> Self {
+        Self(
+            mission
+                .objectives
+                .iter()
+                .map(|definition| Objective {
+                    definition: definition.clone(),
+                    state: ObjectiveState::Inactive,
+                    met: vec![false; definition.complete_when.len()],
+                })
+                .collect(),
+        )
+    }
+}
+
+#[derive(Debug, Reflect)]
+pub(crate) struct Objective {
+    definition: ObjectiveDefinition,
+    pub(crate) state: ObjectiveState,
+    /// Which of the `complete_when` conditions were already met.
+    met: Vec<bool>,
+}
+
+impl Objective {
+    pub(crate) fn title(&self) -> &str {
+        &self.definition.title
+    }
+
+    pub(crate) fn description(&self) -> &str {
+        &self.definition.description
+    }
+
+    pub(crate) fn is_optional(&self) -> bool {
+        self.definition.optional
+    }
+
+    /// Whether the player knows about the objective yet.
+    pub(crate) fn is_revealed(&self) -> bool {
+        match self.state {
+            ObjectiveState::Inactive => false,
+            ObjectiveState::Active | ObjectiveState::Failed => !self.definition.hidden,
+            ObjectiveState::Complete => true,
+        }
+    }
+}
+
+fn start_mission(
+    mut commands: Commands,
+    level_assets: Res<LevelAssets>,
+    missions: Res<Assets<Mission>>,
+    mut objectives: ResMut<Objectives>,
+) {
+    let Some(mission) = missions.get(&level_assets.mission) else {
+        error!("The mission of the level is not loaded");
+        return;
+    };
+    *objectives = Objectives::from_mission(mission);
+    for definition in &mission.objectives {
+        if definition.active {
+            for change in objectives.transition(&definition.id, ObjectiveState::Active) {
+                commands.trigger(change);
+            }
+        }
+    }
+}
+
+fn meet_condition(
+    met: On<ObjectiveConditionMet>,
+    mut objectives: ResMut<Objectives>,
+    mut commands: Commands,
+) {
+    for change in objectives.meet(&met.0) {
+        commands.trigger(change);
+    }
+}
+
+fn set_objective_state(
+    set: On<SetObjectiveState>,
+    mut objectives: ResMut<Objectives>,
+    mut commands: Commands,
+) {
+    for change in objectives.transition(&set.id, set.state) {
+        commands.trigger(change);
+    }
+}
+
+fn report_visited_dialogue_nodes(started: On<NodeStarted>, mut commands: Commands) {
+    commands.trigger(ObjectiveConditionMet(
+        ObjectiveCondition::DialogueNodeVisited(started.node_name.clone()),
+    ));
+}
+
+fn report_used_entities(interacted: On<Interacted>, names: Query<&Name>, mut commands: Commands) {
+    let Ok(name) = names.get(interacted.entity) else {
+        return;
+    };
+    commands.trigger(ObjectiveConditionMet(ObjectiveCondition::EntityUsed(
+        name.to_string(),
+    )));
+}
+
+fn report_collected_items(add: On<Add, HeldProp>, names: Query<&Name>, mut commands: Commands) {
+    let Ok(name) = names.get(add.entity) else {
+        return;
+    };
+    commands.trigger(ObjectiveConditionMet(ObjectiveCondition::ItemCollected(
+        name.to_string(),
+    )));
+}
+
+#[derive(Default, TypePath)]
+struct MissionLoader;
+
+impl AssetLoader for MissionLoader {
+    type Asset = Mission;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mission = Mission::from_ron(std::str::from_utf8(&bytes)?)?;
+        Ok(mission)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["mission.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ObjectiveCondition::*;
+    use ObjectiveState::*;
+
+    const MISSION: &str = r#"(
+        objectives: [
+            (
+                id: "meet",
+                title: "Meet",
+                active: true,
+                complete_when: [DialogueNodeVisited("Npc"), AreaEntered("camp")],
+                fail_when: [EntityUsed("alarm")],
+                activates: ["follow", "secret"],
+            ),
+            (
+                id: "follow",
+                title: "Follow",
+                complete_when: [AreaEntered("cave"), ItemCollected("torch")],
+                activates: ["leave"],
+            ),
+            (id: "secret", title: "Secret", hidden: true, complete_when: [EntityUsed("lever")]),
+            (id: "leave", title: "Leave", complete_when: [AreaEntered("exit")]),
+        ],
+    )"#;
+
+    fn start() -> Objectives {
+        let mission = Mission::from_ron(MISSION).unwrap();
+        let mut objectives = Objectives::from_mission(&mission);
+        objectives.transition("meet", Active);
+        objectives
+    }
+
+    fn states(objectives: &Objectives) -> Vec<ObjectiveState> {
+        objectives.iter().map(|objective| objective.state).collect()
+    }
+
+    fn changed(changes: Vec<ObjectiveStateChanged>) -> Vec<(String, ObjectiveState)> {
+        changes
+            .into_iter()
+            .map(|change| (change.id, change.state))
+            .collect()
+    }
+
+    #[test]
+    fn completing_activates_the_next_objectives() {
+        let mut objectives = start();
+        assert!(
+            objectives
+                .meet(&DialogueNodeVisited("Npc".into()))
+                .is_empty()
+        );
+        assert_eq!(states(&objectives), [Active, Inactive, Inactive, Inactive]);
+
+        let changes = objectives.meet(&AreaEntered("camp".into()));
+        assert_eq!(
+            changed(changes),
+            [
+                ("meet".to_string(), Complete),
+                ("follow".to_string(), Active),
+                ("secret".to_string(), Active),
+            ]
+        );
+
+        // Conditions only count for objectives that are active when they are met.
+        objectives.meet(&AreaEntered("exit".into()));
+        objectives.meet(&AreaEntered("cave".into()));
+        assert_eq!(states(&objectives), [Complete, Active, Active, Inactive]);
+        let changes = objectives.meet(&ItemCollected("torch".into()));
+        assert_eq!(
+            changed(changes),
+            [
+                ("follow".to_string(), Complete),
+                ("leave".to_string(), Active)
+            ]
+        );
+    }
+
+    #[test]
+    fn fail_conditions_fail_active_objectives() {
+        let mut objectives = start();
+        objectives.meet(&DialogueNodeVisited("Npc".into()));
+        let changes = objectives.meet(&EntityUsed("alarm".into()));
+        assert_eq!(changed(changes), [("meet".to_string(), Failed)]);
+        // Failed objectives don't activate the objectives that follow them.
+        objectives.meet(&AreaEntered("camp".into()));
+        assert_eq!(states(&objectives), [Failed, Inactive, Inactive, Inactive]);
+    }
+
+    #[test]
+    fn transitions_only_report_changes() {
+        let mut objectives = start();
+        assert!(objectives.transition("meet", Active).is_empty());
+        assert!(objectives.transition("missing", Complete).is_empty());
+        // Setting the state directly also activates the following objectives.
+        let changes = objectives.transition("follow", Complete);
+        assert_eq!(
+            changed(changes),
+            [
+                ("follow".to_string(), Complete),
+                ("leave".to_string(), Active)
+            ]
+        );
+    }
+
+    #[test]
+    fn hidden_objectives_are_revealed_once_complete() {
+        let mut objectives = start();
+        objectives.transition("secret", Active);
+        assert!(!objectives.get("secret").unwrap().is_revealed());
+        assert!(objectives.get("meet").unwrap().is_revealed());
+        assert!(!objectives.get("follow").unwrap().is_revealed());
+
+        objectives.meet(&EntityUsed("lever".into()));
+        assert!(objectives.get("secret").unwrap().is_revealed());
+    }
+
+    #[test]
+    fn rejects_duplicate_ids() {
+        let error = Mission::from_ron(
+            r#"(objectives: [(id: "a", title: "A"), (id: "a", title: "Also A")])"#,
+        )
+        .unwrap_err();
+        assert!(error.contains("\"a\" is used twice"), "{error}");
+    }
+
+    #[test]
+    fn rejects_unknown_activated_ids() {
+        let error = Mission::from_ron(r#"(objectives: [(id: "a", title: "A", activates: ["b"])])"#)
+            .unwrap_err();
+        assert!(error.contains("activates \"b\""), "{error}");
+    }
+}
@@ -5,7 +5,8 @@ use bevy_seedling::sample::AudioSample;
 use bevy_shuffle_bag::ShuffleBag;
 
 use crate::{
-    asset_tracking::LoadResource, third_party::bevy_trenchbroom::GetTrenchbroomModelPath as _,
+    animation::state_machine::AnimationStateMachine, asset_tracking::LoadResource,
+    third_party::bevy_trenchbroom::GetTrenchbroomModelPath as _,
 };
 
 use super::Player;
@@ -14,6 +15,9 @@ pub(super) fn plugin(app: &mut App) {
     app.load_resource::<PlayerAssets>();
 }
 
+/// The animation state machine of the player's view model.
+pub(super) const PLAYER_ANIMATIONS: &str = "animations/view_model.anim.ron";
+
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub(crate) struct PlayerAssets {
@@ -30,9 +34,7 @@ pub(crate) struct PlayerAssets {
     #[dependency]
     pub(crate) jump_start_sounds: ShuffleBag<Handle<AudioSample>>,
     #[dependency]
-    pub(crate) idle_animation: Handle<AnimationClip>,
-    #[dependency]
-    pub(crate) a_pose_animation: Handle<AnimationClip>,
+    pub(crate) _animations: Handle<AnimationStateMachine>,
 }
 
 impl FromWorld for PlayerAssets {
@@ -97,8 +99,7 @@ impl FromWorld for PlayerAssets {
                 rng,
             )
             .unwrap(),
-            idle_animation: assets.load(Player::animation_path(9)),
-            a_pose_animation: assets.load(Player::animation_path(5)),
+            _animations: assets.load(PLAYER_ANIMATIONS),
         }
     }
 }
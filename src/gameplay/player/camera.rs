@@ -7,18 +7,14 @@ use std::iter;
 
 use avian_pickup::prelude::*;
 use avian3d::prelude::*;
-#[cfg(feature = "native")]
-use bevy::pbr::ScreenSpaceAmbientOcclusion;
 use bevy::{
-    anti_alias::{fxaa::Fxaa, taa::TemporalAntiAliasing},
     camera::{Exposure, visibility::RenderLayers},
     core_pipeline::{
         Skybox,
         prepass::{DeferredPrepass, DepthPrepass},
         tonemapping::Tonemapping,
     },
-    light::{NotShadowCaster, ShadowFilteringMethod},
-    post_process::bloom::Bloom,
+    light::NotShadowCaster,
     prelude::*,
     render::view::Hdr,
     scene::SceneInstanceReady,
@@ -40,6 +36,7 @@ use super::Player;
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<CameraSensitivity>();
     app.init_resource::<WorldModelFov>();
+    app.init_resource::<ReducedMotion>();
 
     app.add_observer(spawn_view_model);
     app.add_observer(add_render_layers_to_point_light);
@@ -62,7 +59,12 @@ pub(crate) struct PlayerCamera;
 #[derive(Component, Debug, Reflect)]
 #[reflect(Component)]
 #[require(Transform, Visibility)]
-struct WorldModelCamera;
+pub(crate) struct WorldModelCamera;
+
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+#[require(Transform, Visibility)]
+pub(crate) struct ViewModelCamera;
 
 fn spawn_view_model(
     add: On<Add, Player>,
@@ -132,27 +134,20 @@ fn spawn_view_model(
                 ),
                 exposure,
                 Tonemapping::TonyMcMapface,
-                Bloom::NATURAL,
                 Skybox {
                     image: level_assets.env_map_specular.clone(),
                     brightness: 8.0,
                     ..default()
                 },
                 env_map.clone(),
-                (
-                    Msaa::Off,
-                    TemporalAntiAliasing::default(),
-                    ShadowFilteringMethod::Temporal,
-                    DeferredPrepass,
-                ),
-                #[cfg(feature = "native")]
-                // See https://github.com/bevyengine/bevy/issues/20459
-                ScreenSpaceAmbientOcclusion::default(),
+                // Anti-aliasing, bloom and SSAO are added according to the `GraphicsSettings`.
+                (Msaa::Off, DeferredPrepass),
             ));
 
             // Spawn view model camera.
             parent.spawn((
                 Name::new("View Model Camera"),
+                ViewModelCamera,
                 Camera3d::default(),
                 Camera {
                     // Bump the order to render on top of the world model.
@@ -171,7 +166,7 @@ fn spawn_view_model(
                 RenderLayers::from(RenderLayer::VIEW_MODEL),
                 exposure,
                 Tonemapping::TonyMcMapface,
-                (DepthPrepass, Msaa::Off, DeferredPrepass, Fxaa::default()),
+                (DepthPrepass, Msaa::Off, DeferredPrepass),
                 env_map,
             ));
 
@@ -273,3 +268,8 @@ impl Default for CameraSensitivity {
         Self(Vec2::splat(1.0))
     }
 }
+
+/// Accessibility setting that turns off camera movement the player is not in control of, such as dialogue framing.
+#[derive(Resource, Reflect, Debug, Default, Deref, DerefMut)]
+#[reflect(Resource)]
+pub(crate) struct ReducedMotion(pub(crate) bool);
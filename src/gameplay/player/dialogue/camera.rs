@@ -0,0 +1,103 @@
+//! Cinematic framing of the speaker while a dialogue is running.
+//!
+//! The world model camera blends from the player's view towards the [`DialogueShot`] of the speaker's [`YarnNode`],
+//! and back again once the dialogue is completed. We only move the world model camera relative to its
+//! [`PlayerCamera`] parent, so the character controller keeps driving the parent as usual and the player's view
+//! is exactly where they left it once the camera has blended back.
+
+use avian3d::prelude::*;
+use bevy::{prelude::*, transform::TransformSystems};
+
+use crate::{
+    gameplay::player::camera::{PlayerCamera, ReducedMotion, WorldModelCamera},
+    screens::Screen,
+    third_party::bevy_yarnspinner::{DialogueShot, YarnNode},
+};
+
+use super::InConversation;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DialogueFraming>();
+    app.add_systems(
+        PostUpdate,
+        frame_speaker
+            .before(TransformSystems::Propagate)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// How far in front of the speaker's face a close-up is taken from.
+const CLOSE_UP_DISTANCE: f32 = 1.2;
+/// How far below the top of the speaker's collider their eyes roughly are.
+const EYE_DEPTH: f32 = 0.15;
+
+#[derive(Resource, Debug, Default, Reflect)]
+#[reflect(Resource)]
+struct DialogueFraming {
+    /// How far the camera has blended from the player's view (0) to the shot (1).
+    blend: f32,
+    /// The framed camera in world space. Kept after the dialogue is completed so that we can blend back from it.
+    shot: Option<Transform>,
+}
+
+fn frame_speaker(
+    player_camera: Single<&Transform, (With<PlayerCamera>, Without<WorldModelCamera>)>,
+    mut world_model_camera: Single<&mut Transform, With<WorldModelCamera>>,
+    speakers: Query<(&YarnNode, &ColliderAabb), With<InConversation>>,
+    reduced_motion: Res<ReducedMotion>,
+    mut framing: ResMut<DialogueFraming>,
+    time: Res<Time>,
+) {
+    // The player camera has no parent, so its transform is already the one in world space.
+    let view = **player_camera;
+    let shot = speakers
+        .iter()
+        .next()
+        .and_then(|(node, aabb)| frame_shot(node.shot, view, aabb));
+    if shot.is_some() {
+        framing.shot = shot;
+    }
+
+    if reduced_motion.0 {
+        framing.blend = 0.0;
+    } else {
+        let target = if shot.is_some() { 1.0 } else { 0.0 };
+        let decay_rate = f32::ln(20.0);
+        framing
+            .blend
+            .smooth_nudge(&target, decay_rate, time.delta_secs());
+        if target == 0.0 && framing.blend < 0.001 {
+            framing.blend = 0.0;
+        }
+    }
+
+    let Some(shot) = framing.shot.filter(|_| framing.blend > 0.0) else {
+        if **world_model_camera != Transform::IDENTITY {
+            **world_model_camera = Transform::IDENTITY;
+        }
+        return;
+    };
+    let translation = view.translation.lerp(shot.translation, framing.blend);
+    let rotation = view.rotation.slerp(shot.rotation, framing.blend);
+    let to_local = view.rotation.inverse();
+    **world_model_camera = Transform {
+        translation: to_local * (translation - view.translation),
+        rotation: to_local * rotation,
+        ..default()
+    };
+}
+
+/// Where the camera should be to frame a speaker with the given shot, or `None` if the shot leaves the camera alone.
+fn frame_shot(shot: DialogueShot, view: Transform, speaker: &ColliderAabb) -> Option<Transform> {
+    let head = speaker.center().with_y(speaker.max.y - EYE_DEPTH);
+    let to_player = Dir3::new((view.translation - head).with_y(0.0)).ok()?;
+    let translation = match shot {
+        DialogueShot::None => return None,
+        DialogueShot::CloseUp => head + to_player * CLOSE_UP_DISTANCE,
+        DialogueShot::OverTheShoulder => {
+            let right = Vec3::Y.cross(*to_player);
+            view.translation + to_player * 0.6 + right * 0.4 + Vec3::Y * 0.1
+        }
+    };
+    Some(Transform::from_translation(translation).looking_at(head, Vec3::Y))
+}
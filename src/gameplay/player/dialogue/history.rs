@@ -0,0 +1,157 @@
+//! Records every line presented in a conversation and every option the player chose, so that the player can read
+//! them again in the dialogue history.
+//!
+//! Yarn Spinner doesn't announce which option was selected, and the dialogue view selects options on its own when
+//! their number is pressed or their button is clicked. We look at the same input right before the dialogue view does
+//! to know which option the player picks, and record it once the dialogue runner accepted the selection.
+
+use bevy::prelude::*;
+use bevy_yarnspinner::{
+    events::{PresentLine, PresentOptions},
+    prelude::*,
+};
+use bevy_yarnspinner_example_dialogue_view::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::third_party::bevy_yarnspinner::is_dialogue_running;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DialogueHistory>();
+    app.init_resource::<PresentedOptions>();
+    app.add_observer(remember_presented_options);
+    app.add_observer(record_line);
+    app.add_systems(
+        Update,
+        (
+            pick_option.before(ExampleYarnSpinnerDialogueViewSystemSet),
+            record_chosen_option.after(ExampleYarnSpinnerDialogueViewSystemSet),
+        )
+            .run_if(is_dialogue_running),
+    );
+}
+
+/// Everything said in conversations so far, oldest first. Serializable so that it can be saved with the game.
+#[derive(Resource, Debug, Default, Clone, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub(crate) struct DialogueHistory(Vec<DialogueHistoryEntry>);
+
+impl DialogueHistory {
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &DialogueHistoryEntry> {
+        self.0.iter()
+    }
+}
+
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+pub(crate) struct DialogueHistoryEntry {
+    /// The character that said the line. Options the player chose have no speaker.
+    pub(crate) speaker: Option<String>,
+    pub(crate) text: String,
+    pub(crate) kind: DialogueHistoryKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub(crate) enum DialogueHistoryKind {
+    Line,
+    ChosenOption,
+}
+
+/// The options that were presented last and not chosen from yet, in the order the dialogue view shows them.
+#[derive(Resource, Debug, Default)]
+struct PresentedOptions {
+    texts: Vec<String>,
+    /// The index of the option the player just picked.
+    picked: Option<usize>,
+}
+
+/// The keys the dialogue view selects the first nine options with.
+const OPTION_KEYS: [[KeyCode; 2]; 9] = [
+    [KeyCode::Digit1, KeyCode::Numpad1],
+    [KeyCode::Digit2, KeyCode::Numpad2],
+    [KeyCode::Digit3, KeyCode::Numpad3],
+    [KeyCode::Digit4, KeyCode::Numpad4],
+    [KeyCode::Digit5, KeyCode::Numpad5],
+    [KeyCode::Digit6, KeyCode::Numpad6],
+    [KeyCode::Digit7, KeyCode::Numpad7],
+    [KeyCode::Digit8, KeyCode::Numpad8],
+    [KeyCode::Digit9, KeyCode::Numpad9],
+];
+
+fn remember_presented_options(
+    present: On<PresentOptions>,
+    mut presented_options: ResMut<PresentedOptions>,
+) {
+    *presented_options = PresentedOptions {
+        // The dialogue view only shows the available options.
+        texts: present
+            .options
+            .iter()
+            .filter(|option| option.is_available)
+            .map(|option| option.line.text_without_character_name())
+            .collect(),
+        picked: None,
+    };
+}
+
+/// Finds the option the dialogue view is about to select, if any.
+/// The option buttons are siblings, in the same order as the options.
+fn pick_option(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    pressed_buttons: Query<(Entity, &Interaction, &ChildOf), (With<Button>, Changed<Interaction>)>,
+    children: Query<&Children>,
+    buttons: Query<(), With<Button>>,
+    mut presented_options: ResMut<PresentedOptions>,
+) {
+    if presented_options.texts.is_empty() {
+        return;
+    }
+    let pressed_key = OPTION_KEYS
+        .iter()
+        .position(|keys| keyboard.any_just_pressed(*keys));
+    let clicked_button = pressed_buttons
+        .iter()
+        .filter(|(_, interaction, _)| **interaction == Interaction::Pressed)
+        .find_map(|(button, _, parent)| {
+            children
+                .get(parent.parent())
+                .ok()?
+                .iter()
+                .filter(|sibling| buttons.contains(*sibling))
+                .position(|sibling| sibling == button)
+        });
+    let picked = pressed_key
+        .or(clicked_button)
+        .filter(|index| *index < presented_options.texts.len());
+    if picked.is_some() {
+        presented_options.picked = picked;
+    }
+}
+
+fn record_chosen_option(
+    dialogue_runner: Single<&DialogueRunner>,
+    mut presented_options: ResMut<PresentedOptions>,
+    mut history: ResMut<DialogueHistory>,
+) {
+    let Some(index) = presented_options.picked.take() else {
+        return;
+    };
+    // The dialogue view ignores the input, e.g. while it is still typing out the line before the options.
+    if dialogue_runner.is_waiting_for_option_selection() {
+        return;
+    }
+    let text = presented_options.texts.swap_remove(index);
+    presented_options.texts.clear();
+    history.0.push(DialogueHistoryEntry {
+        speaker: None,
+        text,
+        kind: DialogueHistoryKind::ChosenOption,
+    });
+}
+
+fn record_line(present: On<PresentLine>, mut history: ResMut<DialogueHistory>) {
+    let line = &present.line;
+    history.0.push(DialogueHistoryEntry {
+        speaker: line.character_name().map(ToString::to_string),
+        text: line.text_without_character_name(),
+        kind: DialogueHistoryKind::Line,
+    });
+}
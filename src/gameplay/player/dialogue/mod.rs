@@ -1,109 +1,84 @@
-//! Player dialogue handling. This module starts the Yarn Spinner dialogue when the player starts interacting with an NPC.
+//! Player dialogue handling. This module starts the Yarn Spinner dialogue when the player interacts with an NPC.
 
 use std::any::Any;
 
-use avian3d::prelude::{SpatialQuery, SpatialQueryFilter};
 use bevy::prelude::*;
-use bevy_enhanced_input::prelude::*;
 
 use bevy_yarnspinner::{events::DialogueCompleted, prelude::*};
 
-use crate::{
-    PostPhysicsAppSystems,
-    gameplay::crosshair::CrosshairState,
-    screens::Screen,
-    third_party::{
-        avian3d::CollisionLayer,
-        bevy_yarnspinner::{YarnNode, is_dialogue_running},
-    },
-};
+use crate::{gameplay::crosshair::CrosshairState, third_party::bevy_yarnspinner::YarnNode};
 
+mod camera;
+pub(crate) mod history;
 mod ui;
 
 use super::{
-    Player,
-    camera::PlayerCamera,
-    input::{BlocksInput, Interact},
-    pickup::is_holding_prop,
+    input::BlocksInput,
+    interaction::{Interactable, Interacted, InteractionVerb},
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.configure_sets(
-        Update,
-        (
-            DialogueSystems::UpdateOpportunity,
-            DialogueSystems::UpdateUi,
-        )
-            .chain()
-            .in_set(PostPhysicsAppSystems::ChangeUi),
-    );
-
-    app.add_systems(
-        Update,
-        check_for_dialogue_opportunity
-            .in_set(DialogueSystems::UpdateOpportunity)
-            .run_if(
-                in_state(Screen::Gameplay)
-                    .and(not(is_dialogue_running))
-                    .and(not(is_holding_prop)),
-            ),
-    );
+    app.add_observer(make_speakers_interactable);
     app.add_observer(restore_input_context);
+    app.add_observer(end_conversations);
     app.add_observer(interact_with_dialogue);
 
-    app.add_plugins(ui::plugin);
+    app.add_plugins((camera::plugin, history::plugin, ui::plugin));
 }
 
-#[derive(Debug, SystemSet, Hash, Eq, PartialEq, Clone, Copy)]
-pub(super) enum DialogueSystems {
-    UpdateOpportunity,
-    UpdateUi,
-}
+/// How far away the player can start talking to someone.
+const MAX_TALKING_DISTANCE: f32 = 3.0;
 
-fn check_for_dialogue_opportunity(
-    player: Single<&GlobalTransform, With<PlayerCamera>>,
-    player_collider: Single<Entity, With<Player>>,
-    mut interaction_prompt: Single<&mut InteractionPrompt>,
-    q_yarn_node: Query<&YarnNode>,
-    spatial_query: SpatialQuery,
+fn make_speakers_interactable(
+    add: On<Add, YarnNode>,
+    nodes: Query<&YarnNode>,
+    mut commands: Commands,
 ) {
-    let camera_transform = player.compute_transform();
-    const MAX_INTERACTION_DISTANCE: f32 = 3.0;
-    let hit = spatial_query.cast_ray(
-        camera_transform.translation,
-        camera_transform.forward(),
-        MAX_INTERACTION_DISTANCE,
-        true,
-        &SpatialQueryFilter::from_mask(CollisionLayer::Character)
-            .with_excluded_entities([*player_collider]),
+    let Ok(node) = nodes.get(add.entity) else {
+        return;
+    };
+    commands.entity(add.entity).insert(
+        Interactable::new(InteractionVerb::Talk, node.prompt.clone())
+            .with_range(MAX_TALKING_DISTANCE)
+            // Talking to someone is more important than picking up the props around them.
+            .with_priority(1),
     );
-    let node = hit
-        .and_then(|hit| q_yarn_node.get(hit.entity).ok())
-        .cloned();
-    if interaction_prompt.0 != node {
-        interaction_prompt.0 = node;
-    }
 }
 
-#[derive(Component, Default, Reflect)]
-#[reflect(Component, Default)]
-struct InteractionPrompt(Option<YarnNode>);
+/// Marks the characters the player is currently talking to. Removed on [`DialogueCompleted`].
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub(crate) struct InConversation;
 
 fn interact_with_dialogue(
-    _on: On<Start<Interact>>,
-    mut interaction_prompt: Single<&mut InteractionPrompt>,
+    interacted: On<Interacted>,
+    nodes: Query<&YarnNode>,
+    mut commands: Commands,
+) {
+    let Ok(node) = nodes.get(interacted.entity) else {
+        return;
+    };
+    commands.run_system_cached_with(start_dialogue, node.yarn_node.clone());
+}
+
+/// Starts the given Yarn node and hands the controls over to the dialogue UI until the dialogue is completed.
+/// Everyone whose [`YarnNode`] is the started node is [`InConversation`] in the meantime.
+pub(crate) fn start_dialogue(
+    In(node): In<String>,
     mut dialogue_runner: Single<&mut DialogueRunner>,
     mut crosshair: Single<&mut CrosshairState>,
     mut blocks_input: ResMut<BlocksInput>,
+    speakers: Query<(Entity, &YarnNode)>,
+    mut commands: Commands,
 ) {
-    let Some(node) = interaction_prompt.0.take() else {
-        return;
-    };
-    dialogue_runner.start_node(&node.yarn_node);
-    blocks_input.insert(interact_with_dialogue.type_id());
-    crosshair
-        .wants_free_cursor
-        .insert(interact_with_dialogue.type_id());
+    dialogue_runner.start_node(&node);
+    for (speaker, speaker_node) in &speakers {
+        if speaker_node.yarn_node == node {
+            commands.entity(speaker).insert(InConversation);
+        }
+    }
+    blocks_input.insert(start_dialogue.type_id());
+    crosshair.wants_free_cursor.insert(start_dialogue.type_id());
 }
 
 fn restore_input_context(
@@ -111,8 +86,18 @@ fn restore_input_context(
     mut crosshair: Single<&mut CrosshairState>,
     mut blocks_input: ResMut<BlocksInput>,
 ) {
-    blocks_input.remove(&interact_with_dialogue.type_id());
+    blocks_input.remove(&start_dialogue.type_id());
     crosshair
         .wants_free_cursor
-        .remove(&interact_with_dialogue.type_id());
+        .remove(&start_dialogue.type_id());
+}
+
+fn end_conversations(
+    _complete: On<DialogueCompleted>,
+    speakers: Query<Entity, With<InConversation>>,
+    mut commands: Commands,
+) {
+    for speaker in &speakers {
+        commands.entity(speaker).remove::<InConversation>();
+    }
 }
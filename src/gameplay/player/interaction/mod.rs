@@ -0,0 +1,212 @@
+//! Lets the player interact with whatever they are looking at.
+//!
+//! Everything the player can interact with has an [`Interactable`]. A single ray from the camera resolves the
+//! [`InteractionTarget`]: the interactable with the highest priority among the ones within their range, where level
+//! geometry hides everything behind it. Pressing [`Interact`] triggers [`Interacted`] on the target, which the
+//! systems implementing each [`InteractionVerb`] observe, e.g. dialogue for [`InteractionVerb::Talk`].
+
+use std::iter;
+
+use avian_pickup::prelude::AvianPickupActor;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+
+use crate::{
+    PostPhysicsAppSystems,
+    screens::Screen,
+    third_party::{avian3d::CollisionLayer, bevy_yarnspinner::is_dialogue_running},
+};
+
+use super::{Player, camera::PlayerCamera, input::Interact, pickup::is_holding_prop};
+
+mod ui;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<InteractionTarget>();
+    app.configure_sets(
+        Update,
+        (InteractionSystems::FindTarget, InteractionSystems::UpdateUi)
+            .chain()
+            .in_set(PostPhysicsAppSystems::ChangeUi),
+    );
+    app.add_systems(
+        Update,
+        (
+            find_interaction_target.run_if(not(is_dialogue_running.or(is_holding_prop))),
+            clear_interaction_target.run_if(is_dialogue_running.or(is_holding_prop)),
+        )
+            .in_set(InteractionSystems::FindTarget)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_observer(interact);
+
+    app.add_plugins(ui::plugin);
+}
+
+#[derive(Debug, SystemSet, Hash, Eq, PartialEq, Clone, Copy)]
+enum InteractionSystems {
+    FindTarget,
+    UpdateUi,
+}
+
+/// Something the player can interact with by looking at it.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub(crate) struct Interactable {
+    pub(crate) verb: InteractionVerb,
+    /// Shown next to the input hint, e.g. "Talk".
+    pub(crate) prompt: String,
+    /// How far away the player can be, in meters.
+    /// Props to [`InteractionVerb::PickUp`] use the [`AvianPickupActor::interaction_distance`] instead.
+    pub(crate) range: f32,
+    /// When several interactables are in line, the one with the highest priority wins.
+    pub(crate) priority: i32,
+}
+
+impl Interactable {
+    pub(crate) fn new(verb: InteractionVerb, prompt: impl Into<String>) -> Self {
+        Self {
+            verb,
+            prompt: prompt.into(),
+            range: 2.0,
+            priority: 0,
+        }
+    }
+
+    pub(crate) fn with_range(mut self, range: f32) -> Self {
+        self.range = range;
+        self
+    }
+
+    pub(crate) fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+/// What interacting with an [`Interactable`] does.
+#[derive(Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum InteractionVerb {
+    /// Handled by observing [`Interacted`].
+    #[default]
+    Use,
+    /// Starts the dialogue of the entity's [`YarnNode`](crate::third_party::bevy_yarnspinner::YarnNode).
+    Talk,
+    /// Opens the entity's [`Readable`](crate::gameplay::readable::Readable) in the reader.
+    Read,
+    /// Picking up props is handled by `avian_pickup`, which has its own input instead of [`Interact`].
+    PickUp,
+}
+
+impl InteractionVerb {
+    /// The input to press for this verb.
+    fn input_hint(self) -> &'static str {
+        match self {
+            Self::Use | Self::Talk | Self::Read => "E",
+            Self::PickUp => "RMB",
+        }
+    }
+}
+
+/// The [`Interactable`] the player would interact with right now.
+#[derive(Resource, Debug, Default, Reflect)]
+#[reflect(Resource)]
+pub(crate) struct InteractionTarget(pub(crate) Option<Entity>);
+
+/// Triggered on an [`Interactable`] when the player interacts with it.
+#[derive(EntityEvent, Debug)]
+pub(crate) struct Interacted {
+    pub(crate) entity: Entity,
+}
+
+/// The longest range any interactable can have.
+const MAX_INTERACTION_DISTANCE: f32 = 5.0;
+/// How many colliders along the ray are considered.
+const MAX_RAY_HITS: u32 = 8;
+
+fn find_interaction_target(
+    camera: Single<(&GlobalTransform, &AvianPickupActor), With<PlayerCamera>>,
+    player: Single<Entity, With<Player>>,
+    interactables: Query<&Interactable>,
+    parents: Query<&ChildOf>,
+    sensors: Query<(), With<Sensor>>,
+    spatial_query: SpatialQuery,
+    mut target: ResMut<InteractionTarget>,
+) {
+    let (camera, pickup_actor) = camera.into_inner();
+    let camera_transform = camera.compute_transform();
+    let mut hits = spatial_query.ray_hits(
+        camera_transform.translation,
+        camera_transform.forward(),
+        MAX_INTERACTION_DISTANCE,
+        MAX_RAY_HITS,
+        true,
+        &SpatialQueryFilter::from_mask([
+            CollisionLayer::Default,
+            CollisionLayer::Prop,
+            CollisionLayer::Character,
+        ])
+        .with_excluded_entities([*player]),
+    );
+    hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+
+    let mut best: Option<(Entity, &Interactable)> = None;
+    for hit in hits {
+        // Colliders are often descendants of the entity that is interactable, e.g. for props.
+        let interactable = iter::once(hit.entity)
+            .chain(parents.iter_ancestors(hit.entity))
+            .find_map(|entity| {
+                interactables
+                    .get(entity)
+                    .ok()
+                    .map(|interactable| (entity, interactable))
+            });
+        match interactable {
+            Some((entity, interactable)) => {
+                let range = match interactable.verb {
+                    // Only props within this distance can actually be picked up.
+                    InteractionVerb::PickUp => pickup_actor.interaction_distance,
+                    _ => interactable.range,
+                };
+                let in_range = hit.distance <= range;
+                // The hits are sorted, so the closest one wins ties.
+                if in_range && best.is_none_or(|(_, best)| interactable.priority > best.priority) {
+                    best = Some((entity, interactable));
+                }
+            }
+            None if sensors.contains(hit.entity) => {}
+            // Level geometry hides everything behind it.
+            None => break,
+        }
+    }
+
+    let best = best.map(|(entity, _)| entity);
+    if target.0 != best {
+        target.0 = best;
+    }
+}
+
+fn clear_interaction_target(mut target: ResMut<InteractionTarget>) {
+    if target.0.is_some() {
+        target.0 = None;
+    }
+}
+
+fn interact(
+    _on: On<Start<Interact>>,
+    target: Res<InteractionTarget>,
+    interactables: Query<&Interactable>,
+    mut commands: Commands,
+) {
+    let Some(entity) = target.0 else {
+        return;
+    };
+    if interactables
+        .get(entity)
+        .is_ok_and(|interactable| interactable.verb == InteractionVerb::PickUp)
+    {
+        return;
+    }
+    commands.trigger(Interacted { entity });
+}
@@ -0,0 +1,73 @@
+//! Shows what the player can interact with: the crosshair turns into a square and a prompt names the input and verb.
+
+use std::any::Any as _;
+
+use bevy::prelude::*;
+
+use crate::{gameplay::crosshair::CrosshairState, screens::Screen};
+
+use super::{Interactable, InteractionSystems, InteractionTarget};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), setup_interaction_prompt);
+    app.add_systems(
+        Update,
+        update_interaction_prompt_ui
+            .in_set(InteractionSystems::UpdateUi)
+            .run_if(in_state(Screen::Gameplay).and(resource_changed::<InteractionTarget>)),
+    );
+}
+
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct InteractionPrompt;
+
+fn setup_interaction_prompt(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Interaction Prompt"),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                left: Val::Percent(50.0),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            DespawnOnExit(Screen::Gameplay),
+            Pickable::IGNORE,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    left: Val::Px(50.0),
+                    ..default()
+                },
+                Text::new(""),
+                Visibility::Hidden,
+                InteractionPrompt,
+            ));
+        });
+}
+
+fn update_interaction_prompt_ui(
+    prompt: Single<(&mut Text, &mut Visibility), With<InteractionPrompt>>,
+    mut crosshair: Single<&mut CrosshairState>,
+    target: Res<InteractionTarget>,
+    interactables: Query<&Interactable>,
+) {
+    let (mut text, mut prompt_visibility) = prompt.into_inner();
+    let system_id = update_interaction_prompt_ui.type_id();
+    if let Some(interactable) = target.0.and_then(|entity| interactables.get(entity).ok()) {
+        text.0 = format!(
+            "{}: {}",
+            interactable.verb.input_hint(),
+            interactable.prompt
+        );
+        *prompt_visibility = Visibility::Inherited;
+        crosshair.wants_square.insert(system_id);
+    } else {
+        text.0 = String::new();
+        *prompt_visibility = Visibility::Hidden;
+        crosshair.wants_square.remove(&system_id);
+    }
+}
@@ -3,7 +3,7 @@
 //! Note that this is separate from the `movement` module as that could be used
 //! for other characters as well.
 
-use animation::{PlayerAnimationState, setup_player_animations};
+use assets::PLAYER_ANIMATIONS;
 use avian3d::prelude::*;
 use bevy::prelude::*;
 use bevy_ahoy::prelude::*;
@@ -14,7 +14,7 @@ use input::PlayerInputContext;
 use navmesh_position::LastValidPlayerNavmeshPosition;
 
 use crate::{
-    animation::AnimationState,
+    animation::state_machine::AnimationController,
     asset_tracking::LoadResource,
     third_party::{avian3d::CollisionLayer, bevy_trenchbroom::GetTrenchbroomModelPath as _},
 };
@@ -24,6 +24,7 @@ pub(crate) mod assets;
 pub(crate) mod camera;
 pub(crate) mod dialogue;
 pub(crate) mod input;
+pub(crate) mod interaction;
 pub(crate) mod movement_sound;
 pub(crate) mod navmesh_position;
 pub(crate) mod pickup;
@@ -34,6 +35,7 @@ pub(super) fn plugin(app: &mut App) {
         assets::plugin,
         camera::plugin,
         input::plugin,
+        interaction::plugin,
         dialogue::plugin,
         movement_sound::plugin,
         pickup::plugin,
@@ -63,40 +65,38 @@ const PLAYER_HALF_HEIGHT: f32 = PLAYER_HEIGHT / 2.0;
 /// using a spring. It's important to make sure that this floating height is greater (even if by little) than the half height.
 ///
 /// In this case, we use 30 cm of padding to make the player float nicely up stairs.
-const PLAYER_FLOAT_HEIGHT: f32 = PLAYER_HALF_HEIGHT + 0.01;
+pub(crate) const PLAYER_FLOAT_HEIGHT: f32 = PLAYER_HALF_HEIGHT + 0.01;
 
 fn setup_player(
     add: On<Add, Player>,
     mut commands: Commands,
     archipelago: Single<Entity, With<Archipelago3d>>,
+    assets: Res<AssetServer>,
 ) {
-    commands
-        .entity(add.entity)
-        .insert((
-            RigidBody::Kinematic,
-            PlayerInputContext,
-            // The player character needs to be configured as a dynamic rigid body of the physics
-            // engine.
-            Collider::cylinder(PLAYER_RADIUS, PLAYER_HEIGHT),
-            // This is Tnua's interface component.
-            CharacterController::default(),
-            ColliderDensity(1_000.0),
-            CollisionLayers::new(CollisionLayer::Character, LayerMask::ALL),
-            AnimationState::<PlayerAnimationState>::default(),
-            children![(
-                Name::new("Player Landmass Character"),
-                Transform::from_xyz(0.0, -PLAYER_FLOAT_HEIGHT, 0.0),
-                Character3dBundle {
-                    character: Character::default(),
-                    settings: CharacterSettings {
-                        radius: PLAYER_RADIUS,
-                    },
-                    archipelago_ref: ArchipelagoRef3d::new(*archipelago),
+    commands.entity(add.entity).insert((
+        RigidBody::Kinematic,
+        PlayerInputContext,
+        // The player character needs to be configured as a dynamic rigid body of the physics
+        // engine.
+        Collider::cylinder(PLAYER_RADIUS, PLAYER_HEIGHT),
+        // This is Tnua's interface component.
+        CharacterController::default(),
+        ColliderDensity(1_000.0),
+        CollisionLayers::new(CollisionLayer::Character, LayerMask::ALL),
+        AnimationController::new(assets.load(PLAYER_ANIMATIONS)),
+        children![(
+            Name::new("Player Landmass Character"),
+            Transform::from_xyz(0.0, -PLAYER_FLOAT_HEIGHT, 0.0),
+            Character3dBundle {
+                character: Character::default(),
+                settings: CharacterSettings {
+                    radius: PLAYER_RADIUS,
                 },
-                LastValidPlayerNavmeshPosition::default(),
-            )],
-        ))
-        .observe(setup_player_animations);
+                archipelago_ref: ArchipelagoRef3d::new(*archipelago),
+            },
+            LastValidPlayerNavmeshPosition::default(),
+        )],
+    ));
 }
 
 fn assert_only_one_player(player: Populated<(), With<Player>>) {
@@ -1,14 +1,31 @@
 //! Player pickup handling.
 
 use avian_pickup::prop::HeldProp;
+use avian3d::prelude::*;
 use bevy::prelude::*;
 
+use super::interaction::{Interactable, InteractionVerb};
+
 mod collision;
 mod sound;
 mod ui;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((collision::plugin, sound::plugin, ui::plugin));
+    app.add_observer(make_props_interactable);
+}
+
+/// `avian_pickup` can pick up any dynamic rigid body.
+fn make_props_interactable(
+    add: On<Add, RigidBody>,
+    bodies: Query<&RigidBody, Without<Interactable>>,
+    mut commands: Commands,
+) {
+    if bodies.get(add.entity).is_ok_and(|body| body.is_dynamic()) {
+        commands
+            .entity(add.entity)
+            .insert(Interactable::new(InteractionVerb::PickUp, "Pick up"));
+    }
 }
 
 pub(crate) fn is_holding_prop(q_prop: Query<&HeldProp>) -> bool {
@@ -6,7 +6,9 @@ use bevy::prelude::*;
 use bevy_seedling::prelude::*;
 
 use crate::{
-    PostPhysicsAppSystems, audio::SpatialPool, gameplay::player::assets::PlayerAssets,
+    PostPhysicsAppSystems,
+    audio::{SpatialPool, caption::Caption},
+    gameplay::player::assets::PlayerAssets,
     screens::Screen,
 };
 
@@ -30,5 +32,6 @@ fn play_throw_sound(
         **player_transform,
         SamplePlayer::new(sound).with_volume(Volume::Linear(3.0)),
         SpatialPool,
+        Caption::new("[object thrown]"),
     ));
 }
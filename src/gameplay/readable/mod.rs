@@ -0,0 +1,252 @@
+//! Notes, letters and books the player can read.
+//!
+//! A [`Readable`] either has its `text` written inline in TrenchBroom, where `\n` starts a new line and `\p` a new
+//! page, or points to a text file in `assets` with `text_file`, where a line containing only `---` starts a new page.
+//! Pages that are too long for the reader are split up further. Every document the player reads is added to the
+//! [`ReadDocuments`], so that a journal can list them.
+
+use avian3d::prelude::*;
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use bevy_trenchbroom::prelude::*;
+
+use crate::{
+    asset_tracking::LoadResource as _,
+    gameplay::player::interaction::{Interactable, InteractionVerb},
+    props::setup::static_bundle,
+    screens::Screen,
+    third_party::bevy_trenchbroom::GetTrenchbroomModelPath as _,
+};
+
+pub(crate) mod ui;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<ReadableText>();
+    app.init_asset_loader::<ReadableTextLoader>();
+    app.init_resource::<ReadDocuments>();
+    app.add_observer(setup_readable);
+    app.add_observer(setup_readable_book);
+    app.add_systems(
+        Update,
+        make_loaded_readables_interactable.run_if(in_state(Screen::Gameplay)),
+    );
+    app.load_asset::<Gltf>(ReadableBook::model_path());
+
+    app.add_plugins(ui::plugin);
+}
+
+/// Something the player can read. Without a model, it is an invisible spot the player can look at, e.g. on a sign.
+#[point_class(base(Transform, Visibility))]
+#[derive(Default)]
+pub(crate) struct Readable {
+    pub(crate) title: String,
+    /// The text, with `\n` for line breaks and `\p` for page breaks. Ignored when `text_file` is set.
+    pub(crate) text: String,
+    /// A text file to read the text from, relative to `assets`, e.g. `readables/welcome.txt`.
+    pub(crate) text_file: String,
+}
+
+/// A book lying on a surface.
+#[point_class(
+    base(Readable),
+    model("models/darkmod/furniture/shelves/book_red.gltf")
+)]
+pub(crate) struct ReadableBook;
+
+/// The text of a [`Readable`] loaded from a `.txt` file.
+#[derive(Asset, TypePath, Debug)]
+pub(crate) struct ReadableText(String);
+
+/// The handle of the file a [`Readable`] reads its text from.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct ReadableTextFile(Handle<ReadableText>);
+
+/// A document the player has read.
+#[derive(Debug, Clone, Reflect)]
+pub(crate) struct Document {
+    pub(crate) title: String,
+    pub(crate) pages: Vec<String>,
+}
+
+/// Every document the player has read, in the order they first read them.
+#[derive(Resource, Debug, Default, Reflect)]
+#[reflect(Resource)]
+pub(crate) struct ReadDocuments(Vec<Document>);
+
+impl ReadDocuments {
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Document> {
+        self.0.iter()
+    }
+
+    fn insert(&mut self, document: Document) {
+        if !self.0.iter().any(|read| read.title == document.title) {
+            self.0.push(document);
+        }
+    }
+}
+
+/// The radius of the spot the player has to look at to read a [`Readable`] without a model.
+const READABLE_RADIUS: f32 = 0.15;
+/// Pages with more characters than this are split up.
+const MAX_PAGE_LENGTH: usize = 700;
+
+fn setup_readable(
+    add: On<Add, Readable>,
+    readables: Query<&Readable>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let Ok(readable) = readables.get(add.entity) else {
+        return;
+    };
+    let mut entity = commands.entity(add.entity);
+    entity.insert((
+        // Gives the interaction ray something to hit, even when there is no model.
+        Collider::sphere(READABLE_RADIUS),
+        Sensor,
+    ));
+    if readable.text_file.is_empty() {
+        entity.insert(readable_interactable());
+    } else {
+        // Only interactable once the file is loaded, so that the reader never opens without the text.
+        entity.insert(ReadableTextFile(
+            asset_server.load(readable.text_file.clone()),
+        ));
+    }
+}
+
+fn readable_interactable() -> Interactable {
+    Interactable::new(InteractionVerb::Read, "Read")
+}
+
+fn make_loaded_readables_interactable(
+    readables: Query<(Entity, &ReadableTextFile), Without<Interactable>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    for (entity, file) in &readables {
+        if asset_server.is_loaded(&file.0) {
+            commands.entity(entity).insert(readable_interactable());
+        }
+    }
+}
+
+fn setup_readable_book(
+    add: On<Add, ReadableBook>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    commands
+        .entity(add.entity)
+        .insert(static_bundle::<ReadableBook>(
+            &asset_server,
+            ColliderConstructor::ConvexHullFromMesh,
+        ));
+}
+
+impl Readable {
+    /// The document this readable shows, split into pages.
+    fn document(&self, file: Option<&ReadableText>) -> Document {
+        let pages = match file {
+            Some(file) => file.0.split("\n---\n").flat_map(paginate).collect(),
+            None => self
+                .text
+                .replace("\\n", "\n")
+                .split("\\p")
+                .flat_map(paginate)
+                .collect(),
+        };
+        Document {
+            title: self.title.clone(),
+            pages,
+        }
+    }
+}
+
+/// Splits a page that is too long for the reader at word boundaries.
+fn paginate(page: &str) -> Vec<String> {
+    let mut pages = vec![String::new()];
+    for word in page.trim().split_inclusive(char::is_whitespace) {
+        let current = pages.last_mut().unwrap();
+        if !current.is_empty() && current.len() + word.len() > MAX_PAGE_LENGTH {
+            pages.push(String::new());
+        }
+        pages.last_mut().unwrap().push_str(word);
+    }
+    pages
+}
+
+#[derive(Default, TypePath)]
+struct ReadableTextLoader;
+
+impl AssetLoader for ReadableTextLoader {
+    type Asset = ReadableText;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let text = String::from_utf8(bytes)?;
+        // Files saved on Windows would otherwise not match the page breaks.
+        Ok(ReadableText(text.replace("\r\n", "\n")))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["txt"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paginate_keeps_short_pages() {
+        assert_eq!(paginate("  A short page.\n"), ["A short page."]);
+        assert_eq!(paginate(""), [""]);
+    }
+
+    #[test]
+    fn paginate_splits_long_pages_between_words() {
+        let word = "word ";
+        let page = word.repeat(MAX_PAGE_LENGTH / word.len() + 10);
+        let pages = paginate(&page);
+        assert_eq!(pages.len(), 2);
+        assert!(pages.iter().all(|page| page.len() <= MAX_PAGE_LENGTH));
+        assert!(pages.iter().all(|page| page.starts_with("word")));
+        assert_eq!(pages.concat(), page.trim());
+    }
+
+    #[test]
+    fn inline_text_uses_escaped_breaks() {
+        let readable = Readable {
+            title: "Note".to_string(),
+            text: "First line\\nSecond line\\pNext page".to_string(),
+            text_file: String::new(),
+        };
+        let document = readable.document(None);
+        assert_eq!(document.title, "Note");
+        assert_eq!(document.pages, ["First line\nSecond line", "Next page"]);
+    }
+
+    #[test]
+    fn text_files_use_separator_lines() {
+        let readable = Readable {
+            title: "Letter".to_string(),
+            text: "Ignored".to_string(),
+            text_file: "readables/letter.txt".to_string(),
+        };
+        let file = ReadableText("Dear reader,\n---\nGoodbye.\n".to_string());
+        let document = readable.document(Some(&file));
+        assert_eq!(document.pages, ["Dear reader,", "Goodbye."]);
+    }
+}
@@ -0,0 +1,171 @@
+//! The reader that shows a [`Readable`] page by page. While it is open, player input is blocked and the cursor is free.
+
+use std::any::Any as _;
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+
+use crate::{
+    gameplay::{
+        crosshair::CrosshairState,
+        player::{input::BlocksInput, interaction::Interacted},
+    },
+    screens::Screen,
+    theme::{
+        navigation::{NavigableOverlay, back_just_pressed, gamepad_just_pressed},
+        palette::SCREEN_BACKGROUND,
+        widget,
+    },
+};
+
+use super::{Document, ReadDocuments, Readable, ReadableText, ReadableTextFile};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(open_reader);
+    app.add_systems(
+        Update,
+        (
+            close_reader.run_if(back_just_pressed),
+            // The arrow keys move the focus between the reader's buttons, see `theme::navigation`.
+            previous_page.run_if(
+                input_just_pressed(KeyCode::PageUp)
+                    .or(gamepad_just_pressed(GamepadButton::LeftTrigger)),
+            ),
+            next_page.run_if(
+                input_just_pressed(KeyCode::PageDown)
+                    .or(gamepad_just_pressed(GamepadButton::RightTrigger)),
+            ),
+            update_reader_page,
+        )
+            .chain()
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct ReaderUi {
+    document: Document,
+    page: usize,
+}
+
+/// Whether the reader is open. The reader takes precedence over opening the pause menu.
+pub(crate) fn is_reader_open(reader: Query<(), With<ReaderUi>>) -> bool {
+    !reader.is_empty()
+}
+
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct ReaderPageText;
+
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct ReaderPageNumber;
+
+fn open_reader(
+    interacted: On<Interacted>,
+    readables: Query<(&Readable, Option<&ReadableTextFile>)>,
+    texts: Res<Assets<ReadableText>>,
+    existing_reader: Query<(), With<ReaderUi>>,
+    mut read_documents: ResMut<ReadDocuments>,
+    mut crosshair: Single<&mut CrosshairState>,
+    mut blocks_input: ResMut<BlocksInput>,
+    mut commands: Commands,
+) {
+    let Ok((readable, file)) = readables.get(interacted.entity) else {
+        return;
+    };
+    if !existing_reader.is_empty() {
+        return;
+    }
+    let file = file.and_then(|file| texts.get(&file.0));
+    let document = readable.document(file);
+    read_documents.insert(document.clone());
+
+    commands.spawn((
+        widget::ui_root("Reader"),
+        ReaderUi { document, page: 0 },
+        NavigableOverlay,
+        BackgroundColor(SCREEN_BACKGROUND.with_alpha(0.95)),
+        GlobalZIndex(1),
+        DespawnOnExit(Screen::Gameplay),
+        children![
+            widget::header(readable.title.clone()),
+            (
+                Name::new("Page"),
+                Node {
+                    width: Px(700.0),
+                    min_height: Px(400.0),
+                    ..default()
+                },
+                children![(widget::label(""), ReaderPageText)],
+            ),
+            (
+                Name::new("Page Controls"),
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Px(20.0),
+                    ..default()
+                },
+                children![
+                    widget::button_small("<", previous_page_on_click),
+                    (widget::label(""), ReaderPageNumber),
+                    widget::button_small(">", next_page_on_click),
+                ],
+            ),
+            widget::button("Close", close_reader_on_click),
+        ],
+    ));
+    blocks_input.insert(open_reader.type_id());
+    crosshair.wants_free_cursor.insert(open_reader.type_id());
+    crosshair.wants_invisible.insert(open_reader.type_id());
+}
+
+fn close_reader_on_click(_on: On<Pointer<Click>>, mut commands: Commands) {
+    commands.run_system_cached(close_reader);
+}
+
+fn close_reader(
+    reader: Single<Entity, With<ReaderUi>>,
+    mut crosshair: Single<&mut CrosshairState>,
+    mut blocks_input: ResMut<BlocksInput>,
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    mut commands: Commands,
+) {
+    // The same press would otherwise also open the pause menu once the reader is gone.
+    keyboard.clear_just_pressed(KeyCode::Escape);
+    commands.entity(*reader).despawn();
+    blocks_input.remove(&open_reader.type_id());
+    crosshair.wants_free_cursor.remove(&open_reader.type_id());
+    crosshair.wants_invisible.remove(&open_reader.type_id());
+}
+
+fn previous_page_on_click(_on: On<Pointer<Click>>, mut commands: Commands) {
+    commands.run_system_cached(previous_page);
+}
+
+fn next_page_on_click(_on: On<Pointer<Click>>, mut commands: Commands) {
+    commands.run_system_cached(next_page);
+}
+
+fn previous_page(mut reader: Single<&mut ReaderUi>) {
+    reader.page = reader.page.saturating_sub(1);
+}
+
+fn next_page(mut reader: Single<&mut ReaderUi>) {
+    let last_page = reader.document.pages.len().saturating_sub(1);
+    reader.page = (reader.page + 1).min(last_page);
+}
+
+fn update_reader_page(
+    reader: Single<&ReaderUi, Changed<ReaderUi>>,
+    mut page_text: Single<&mut Text, (With<ReaderPageText>, Without<ReaderPageNumber>)>,
+    mut page_number: Single<&mut Text, With<ReaderPageNumber>>,
+) {
+    page_text.0 = reader
+        .document
+        .pages
+        .get(reader.page)
+        .cloned()
+        .unwrap_or_default();
+    page_number.0 = format!("{} / {}", reader.page + 1, reader.document.pages.len());
+}
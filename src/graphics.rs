@@ -0,0 +1,558 @@
+//! Graphics quality settings. The [`GraphicsSettings`] are applied live to the world and view model cameras, the
+//! shadow maps, the lights and the particle effects.
+//!
+//! When the render scale is below 100%, the world and view model cameras render into an image smaller than the window,
+//! which is then stretched over the window behind all other UI. The UI camera always renders at the native resolution.
+//!
+//! With dynamic resolution enabled, the render scale is additionally lowered while frames take longer than the target
+//! frame time and raised again once there is headroom, but never above the chosen render scale. When vsync or the frame
+//! rate limiter hold frames to a lower rate than the target, that rate becomes the target.
+
+use std::time::Duration;
+
+#[cfg(feature = "native")]
+use bevy::pbr::ScreenSpaceAmbientOcclusion;
+use bevy::{
+    anti_alias::{fxaa::Fxaa, taa::TemporalAntiAliasing},
+    asset::RenderAssetUsages,
+    camera::{ImageRenderTarget, RenderTarget},
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    light::{DirectionalLightShadowMap, PointLightShadowMap, ShadowFilteringMethod},
+    math::FloatOrd,
+    post_process::bloom::Bloom,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+    ui::Val::*,
+    window::{Monitor, PresentMode, PrimaryMonitor, PrimaryWindow, WindowRef},
+};
+use bevy_framepace::{FramepaceSettings, Limiter};
+
+use crate::{
+    PostPhysicsAppSystems,
+    gameplay::player::camera::{PlayerCamera, ViewModelCamera, WorldModelCamera},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+        app.add_plugins(FrameTimeDiagnosticsPlugin::default());
+    }
+    app.init_resource::<GraphicsSettings>();
+    app.init_resource::<RenderScale>();
+    app.init_resource::<DynamicResolutionScale>();
+    app.add_systems(Startup, spawn_render_scale_presenter);
+    app.add_systems(OnExit(Screen::Gameplay), hide_render_scale_presenter);
+    app.add_observer(budget_point_light_shadows);
+    app.add_observer(budget_spot_light_shadows);
+    app.add_systems(
+        Update,
+        (
+            apply_camera_settings.run_if(
+                resource_changed::<GraphicsSettings>
+                    .or(any_match_filter::<Added<WorldModelCamera>>),
+            ),
+            apply_shadow_map_size.run_if(resource_changed::<GraphicsSettings>),
+            adjust_dynamic_resolution,
+            update_render_scale.run_if(
+                resource_changed::<GraphicsSettings>.or(resource_changed::<DynamicResolutionScale>),
+            ),
+            apply_render_scale.run_if(in_state(Screen::Gameplay)),
+            apply_shadow_light_budget.run_if(in_state(Screen::Gameplay)),
+        )
+            .chain()
+            .in_set(PostPhysicsAppSystems::Update),
+    );
+}
+
+/// The player's graphics settings. Start out at [`GraphicsPreset::High`].
+#[derive(Resource, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub(crate) struct GraphicsSettings {
+    pub(crate) anti_aliasing: AntiAliasing,
+    /// Screen space ambient occlusion. Only available in native builds.
+    pub(crate) ssao: bool,
+    pub(crate) bloom: bool,
+    /// The size of each shadow map in pixels.
+    pub(crate) shadow_map_size: usize,
+    /// How many of the lights closest to the camera cast shadows.
+    pub(crate) shadow_light_budget: usize,
+    /// How many particles effects spawn, in percent of their authored spawn rate.
+    pub(crate) particle_density: u32,
+    /// The resolution the world is rendered at, in percent of the window's resolution. The upper bound for dynamic
+    /// resolution.
+    pub(crate) render_scale: u32,
+    /// The frame rate dynamic resolution tries to hold, or `None` to always render at the chosen render scale.
+    /// Not part of the presets, since it depends on the player's display rather than on their hardware.
+    pub(crate) dynamic_resolution: Option<u32>,
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self::from_preset(GraphicsPreset::High)
+    }
+}
+
+impl GraphicsSettings {
+    pub(crate) fn from_preset(preset: GraphicsPreset) -> Self {
+        match preset {
+            GraphicsPreset::Low => Self {
+                anti_aliasing: AntiAliasing::Fxaa,
+                ssao: false,
+                bloom: false,
+                shadow_map_size: 512,
+                shadow_light_budget: 1,
+                particle_density: 25,
+                render_scale: 70,
+                dynamic_resolution: None,
+            },
+            GraphicsPreset::Medium => Self {
+                anti_aliasing: AntiAliasing::Fxaa,
+                ssao: false,
+                bloom: true,
+                shadow_map_size: 1024,
+                shadow_light_budget: 2,
+                particle_density: 50,
+                render_scale: 85,
+                dynamic_resolution: None,
+            },
+            GraphicsPreset::High | GraphicsPreset::Custom => Self {
+                anti_aliasing: AntiAliasing::Taa,
+                ssao: true,
+                bloom: true,
+                shadow_map_size: 2048,
+                shadow_light_budget: 8,
+                particle_density: 100,
+                render_scale: 100,
+                dynamic_resolution: None,
+            },
+        }
+    }
+
+    /// Changes every setting that is part of the preset.
+    pub(crate) fn apply_preset(&mut self, preset: GraphicsPreset) {
+        *self = Self {
+            dynamic_resolution: self.dynamic_resolution,
+            ..Self::from_preset(preset)
+        };
+    }
+
+    /// The preset these settings match, or [`GraphicsPreset::Custom`] if they were changed individually.
+    pub(crate) fn preset(&self) -> GraphicsPreset {
+        [
+            GraphicsPreset::Low,
+            GraphicsPreset::Medium,
+            GraphicsPreset::High,
+        ]
+        .into_iter()
+        .find(|preset| {
+            let mut settings = self.clone();
+            settings.apply_preset(*preset);
+            settings == *self
+        })
+        .unwrap_or(GraphicsPreset::Custom)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub(crate) enum GraphicsPreset {
+    Low,
+    Medium,
+    High,
+    Custom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub(crate) enum AntiAliasing {
+    Off,
+    Fxaa,
+    Taa,
+}
+
+/// The fraction of the window's resolution the world is currently rendered at.
+#[derive(Resource, Debug, Reflect, Deref, DerefMut)]
+#[reflect(Resource)]
+pub(crate) struct RenderScale(pub(crate) f32);
+
+impl Default for RenderScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// How much dynamic resolution currently lowers the render scale, as a factor of
+/// [`GraphicsSettings::render_scale`].
+#[derive(Resource, Debug, Reflect)]
+#[reflect(Resource)]
+struct DynamicResolutionScale(f32);
+
+impl Default for DynamicResolutionScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Shows the image the cameras render into when the [`RenderScale`] is below 1.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct RenderScalePresenter;
+
+/// A light that casts shadows as long as it is within the [`GraphicsSettings::shadow_light_budget`].
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct BudgetedShadows;
+
+fn apply_camera_settings(
+    settings: Res<GraphicsSettings>,
+    world_camera: Single<Entity, With<WorldModelCamera>>,
+    view_model_camera: Single<Entity, With<ViewModelCamera>>,
+    mut commands: Commands,
+) {
+    let mut world_camera = commands.entity(*world_camera);
+    let mut view_model_camera = commands.entity(*view_model_camera);
+    match settings.anti_aliasing {
+        AntiAliasing::Off => {
+            world_camera.remove::<(TemporalAntiAliasing, Fxaa)>();
+            world_camera.insert(ShadowFilteringMethod::Gaussian);
+            view_model_camera.remove::<Fxaa>();
+        }
+        AntiAliasing::Fxaa => {
+            world_camera.remove::<TemporalAntiAliasing>();
+            world_camera.insert((Fxaa::default(), ShadowFilteringMethod::Gaussian));
+            view_model_camera.insert(Fxaa::default());
+        }
+        AntiAliasing::Taa => {
+            world_camera.remove::<Fxaa>();
+            // Temporal shadow filtering relies on TAA to smooth out its noise.
+            world_camera.insert((
+                TemporalAntiAliasing::default(),
+                ShadowFilteringMethod::Temporal,
+            ));
+            view_model_camera.insert(Fxaa::default());
+        }
+    }
+
+    if settings.bloom {
+        world_camera.insert(Bloom::NATURAL);
+    } else {
+        world_camera.remove::<Bloom>();
+    }
+
+    #[cfg(feature = "native")]
+    if settings.ssao {
+        // See https://github.com/bevyengine/bevy/issues/20459
+        world_camera.insert(ScreenSpaceAmbientOcclusion::default());
+    } else {
+        world_camera.remove::<ScreenSpaceAmbientOcclusion>();
+    }
+}
+
+fn apply_shadow_map_size(settings: Res<GraphicsSettings>, mut commands: Commands) {
+    commands.insert_resource(DirectionalLightShadowMap {
+        size: settings.shadow_map_size,
+    });
+    commands.insert_resource(PointLightShadowMap {
+        size: settings.shadow_map_size,
+    });
+}
+
+fn update_render_scale(
+    settings: Res<GraphicsSettings>,
+    dynamic_scale: Res<DynamicResolutionScale>,
+    mut render_scale: ResMut<RenderScale>,
+) {
+    render_scale.0 = settings.render_scale as f32 / 100.0 * dynamic_scale.0;
+}
+
+/// The lowest render scale dynamic resolution goes down to.
+const MIN_DYNAMIC_RENDER_SCALE: f32 = 0.5;
+/// How much the render scale changes per adjustment.
+const DYNAMIC_RESOLUTION_STEP: f32 = 0.05;
+/// How long to wait between adjustments, so that the smoothed frame time can catch up with the last one.
+const DYNAMIC_RESOLUTION_INTERVAL: Duration = Duration::from_millis(500);
+/// The render scale is lowered when frames take this much longer than the target frame time.
+const DYNAMIC_RESOLUTION_LOWER_ABOVE: f64 = 1.1;
+/// The render scale is raised when frames take this much less than the target frame time. The gap to
+/// [`DYNAMIC_RESOLUTION_LOWER_ABOVE`] keeps the scale from oscillating around the target.
+const DYNAMIC_RESOLUTION_RAISE_BELOW: f64 = 0.8;
+/// Frames held to the target by vsync or the frame rate limiter never finish early, so the render scale is raised as
+/// long as they keep up with the target, with some leeway for jitter.
+const DYNAMIC_RESOLUTION_RAISE_BELOW_PACED: f64 = 1.02;
+
+/// The frame rate vsync or the frame rate limiter hold frames to, if any.
+fn paced_frame_rate(
+    present_mode: PresentMode,
+    limiter: Option<&Limiter>,
+    refresh_rate: Option<f64>,
+) -> Option<f64> {
+    let vsync = matches!(
+        present_mode,
+        PresentMode::AutoVsync | PresentMode::Fifo | PresentMode::FifoRelaxed
+    );
+    let vsync_rate = if vsync { refresh_rate } else { None };
+    let limiter_rate = match limiter {
+        Some(Limiter::Manual(frame_time)) => Some(1.0 / frame_time.as_secs_f64()),
+        Some(Limiter::Auto) => refresh_rate,
+        Some(Limiter::Off) | None => None,
+    };
+    [vsync_rate, limiter_rate]
+        .into_iter()
+        .flatten()
+        .reduce(f64::min)
+}
+
+/// Measures whole frames. Since frames can't be faster than vsync or the frame rate limiter allow, the target is capped
+/// at their rate.
+fn adjust_dynamic_resolution(
+    settings: Res<GraphicsSettings>,
+    diagnostics: Res<DiagnosticsStore>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    primary_monitor: Option<Single<&Monitor, With<PrimaryMonitor>>>,
+    framepace: Option<Res<FramepaceSettings>>,
+    mut dynamic_scale: ResMut<DynamicResolutionScale>,
+    // Frame times matter even while the game is paused.
+    time: Res<Time<Real>>,
+    mut since_adjustment: Local<Duration>,
+) {
+    let Some(target_fps) = settings.dynamic_resolution else {
+        if dynamic_scale.0 != 1.0 {
+            dynamic_scale.0 = 1.0;
+        }
+        return;
+    };
+    *since_adjustment += time.delta();
+    if *since_adjustment < DYNAMIC_RESOLUTION_INTERVAL {
+        return;
+    }
+    let Some(frame_time) = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        .and_then(|diagnostic| diagnostic.smoothed())
+    else {
+        return;
+    };
+    // The window may be on another monitor, but the primary one is the best guess we have.
+    let refresh_rate = primary_monitor
+        .and_then(|monitor| monitor.refresh_rate_millihertz)
+        .map(|millihertz| millihertz as f64 / 1000.0);
+    let paced_fps = paced_frame_rate(
+        window.present_mode,
+        framepace.as_ref().map(|framepace| &framepace.limiter),
+        refresh_rate,
+    );
+    let (target_fps, raise_below) = match paced_fps {
+        Some(paced_fps) if paced_fps <= target_fps as f64 => {
+            (paced_fps, DYNAMIC_RESOLUTION_RAISE_BELOW_PACED)
+        }
+        _ => (target_fps as f64, DYNAMIC_RESOLUTION_RAISE_BELOW),
+    };
+    // In milliseconds, like the diagnostic.
+    let target_frame_time = 1000.0 / target_fps;
+    let step = if frame_time > target_frame_time * DYNAMIC_RESOLUTION_LOWER_ABOVE {
+        -DYNAMIC_RESOLUTION_STEP
+    } else if frame_time < target_frame_time * raise_below {
+        DYNAMIC_RESOLUTION_STEP
+    } else {
+        return;
+    };
+    let max_scale = settings.render_scale as f32 / 100.0;
+    let min_factor = (MIN_DYNAMIC_RENDER_SCALE / max_scale).min(1.0);
+    let factor = (dynamic_scale.0 + step / max_scale).clamp(min_factor, 1.0);
+    if factor != dynamic_scale.0 {
+        dynamic_scale.0 = factor;
+    }
+    *since_adjustment = Duration::ZERO;
+}
+
+fn spawn_render_scale_presenter(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    commands.spawn((
+        Name::new("Render Scale Presenter"),
+        RenderScalePresenter,
+        ImageNode::new(images.add(render_target_image(UVec2::ONE))),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Percent(100.0),
+            height: Percent(100.0),
+            ..default()
+        },
+        // Behind all other UI.
+        GlobalZIndex(i32::MIN),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+    ));
+}
+
+fn render_target_image(size: UVec2) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Bgra8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    image
+}
+
+fn apply_render_scale(
+    render_scale: Res<RenderScale>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<&mut RenderTarget, Or<(With<WorldModelCamera>, With<ViewModelCamera>)>>,
+    presenter: Single<(&ImageNode, &mut Visibility), With<RenderScalePresenter>>,
+    mut images: ResMut<Assets<Image>>,
+    mut image_size: Local<UVec2>,
+) {
+    let (presenter, mut visibility) = presenter.into_inner();
+    let scaled = render_scale.0 < 1.0 && !cameras.is_empty();
+    let target = if scaled {
+        let size = (window.physical_size().as_vec2() * render_scale.0)
+            .round()
+            .as_uvec2()
+            .max(UVec2::ONE);
+        if *image_size != size {
+            if let Some(image) = images.get_mut(&presenter.image) {
+                image.resize(Extent3d {
+                    width: size.x,
+                    height: size.y,
+                    depth_or_array_layers: 1,
+                });
+            }
+            *image_size = size;
+        }
+        RenderTarget::Image(ImageRenderTarget {
+            handle: presenter.image.clone(),
+            // Keeps the logical size of the viewport equal to the window's, so that UI placed over the world lines up.
+            scale_factor: FloatOrd(window.scale_factor() * render_scale.0),
+        })
+    } else {
+        RenderTarget::Window(WindowRef::Primary)
+    };
+    for mut camera_target in &mut cameras {
+        camera_target.set_if_neq(target.clone());
+    }
+    visibility.set_if_neq(if scaled {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+}
+
+/// The world cameras are gone outside of gameplay, so nothing renders into the presenter's image anymore.
+fn hide_render_scale_presenter(mut presenter: Single<&mut Visibility, With<RenderScalePresenter>>) {
+    presenter.set_if_neq(Visibility::Hidden);
+}
+
+fn budget_point_light_shadows(
+    add: On<Add, PointLight>,
+    lights: Query<&PointLight>,
+    mut commands: Commands,
+) {
+    if lights
+        .get(add.entity)
+        .is_ok_and(|light| light.shadows_enabled)
+    {
+        commands.entity(add.entity).insert(BudgetedShadows);
+    }
+}
+
+fn budget_spot_light_shadows(
+    add: On<Add, SpotLight>,
+    lights: Query<&SpotLight>,
+    mut commands: Commands,
+) {
+    if lights
+        .get(add.entity)
+        .is_ok_and(|light| light.shadows_enabled)
+    {
+        commands.entity(add.entity).insert(BudgetedShadows);
+    }
+}
+
+/// Only lets the lights closest to the camera cast shadows.
+fn apply_shadow_light_budget(
+    settings: Res<GraphicsSettings>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    mut point_lights: Query<(&GlobalTransform, &mut PointLight), With<BudgetedShadows>>,
+    mut spot_lights: Query<(&GlobalTransform, &mut SpotLight), With<BudgetedShadows>>,
+) {
+    let camera_position = camera.translation();
+    let distance = |transform: &GlobalTransform| transform.translation().distance(camera_position);
+    let mut distances: Vec<_> = point_lights
+        .iter()
+        .map(|(transform, _)| distance(transform))
+        .chain(spot_lights.iter().map(|(transform, _)| distance(transform)))
+        .collect();
+    distances.sort_by(f32::total_cmp);
+    let max_distance = match settings.shadow_light_budget {
+        0 => f32::NEG_INFINITY,
+        budget => distances.get(budget - 1).copied().unwrap_or(f32::INFINITY),
+    };
+
+    for (transform, mut light) in &mut point_lights {
+        let shadows_enabled = distance(transform) <= max_distance;
+        if light.shadows_enabled != shadows_enabled {
+            light.shadows_enabled = shadows_enabled;
+        }
+    }
+    for (transform, mut light) in &mut spot_lights {
+        let shadows_enabled = distance(transform) <= max_distance;
+        if light.shadows_enabled != shadows_enabled {
+            light.shadows_enabled = shadows_enabled;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_are_recognized() {
+        for preset in [
+            GraphicsPreset::Low,
+            GraphicsPreset::Medium,
+            GraphicsPreset::High,
+        ] {
+            assert_eq!(GraphicsSettings::from_preset(preset).preset(), preset);
+        }
+    }
+
+    #[test]
+    fn changed_settings_are_custom() {
+        let settings = GraphicsSettings {
+            shadow_light_budget: 3,
+            ..GraphicsSettings::from_preset(GraphicsPreset::High)
+        };
+        assert_eq!(settings.preset(), GraphicsPreset::Custom);
+    }
+
+    #[test]
+    fn dynamic_resolution_is_not_part_of_presets() {
+        let mut settings = GraphicsSettings {
+            dynamic_resolution: Some(60),
+            ..GraphicsSettings::from_preset(GraphicsPreset::Low)
+        };
+        assert_eq!(settings.preset(), GraphicsPreset::Low);
+        settings.apply_preset(GraphicsPreset::Medium);
+        assert_eq!(settings.preset(), GraphicsPreset::Medium);
+        assert_eq!(settings.dynamic_resolution, Some(60));
+    }
+
+    #[test]
+    fn vsync_and_limiter_pace_frames_to_the_lower_rate() {
+        let limiter = Limiter::from_framerate(30.0);
+        let paced = paced_frame_rate(PresentMode::AutoVsync, Some(&limiter), Some(60.0));
+        assert!(paced.is_some_and(|fps| (fps - 30.0).abs() < 0.01));
+        assert_eq!(
+            paced_frame_rate(PresentMode::AutoVsync, Some(&Limiter::Off), Some(60.0)),
+            Some(60.0)
+        );
+        assert_eq!(
+            paced_frame_rate(PresentMode::AutoNoVsync, Some(&Limiter::Off), Some(60.0)),
+            None
+        );
+    }
+}
@@ -5,14 +5,18 @@ mod animation;
 mod asset_processing;
 mod asset_tracking;
 mod audio;
+#[cfg(feature = "native")]
+mod cli;
 #[cfg(feature = "dev")]
 mod dev_tools;
 mod gameplay;
+mod graphics;
 mod hdr;
 mod menus;
 mod props;
 mod screens;
 mod shader_compilation;
+mod stable_hash;
 mod theme;
 mod third_party;
 mod ui_camera;
@@ -27,7 +31,10 @@ use bevy::{camera::visibility::RenderLayers, ecs::error::error};
 use bevy_seedling::SeedlingPlugin;
 use bitflags::bitflags;
 
-use bevy::{asset::AssetMetaCheck, prelude::*};
+use bevy::{
+    asset::{AssetMetaCheck, io::AssetSourceId},
+    prelude::*,
+};
 
 #[cfg(all(feature = "native", feature = "web"))]
 compile_error!(
@@ -47,12 +54,23 @@ compile_error!(
 );
 
 fn main() -> AppExit {
+    // Tooling commands like `--export-trenchbroom-config` run headless and exit instead of starting the game.
+    #[cfg(feature = "native")]
+    if let Some(exit) = cli::run_command() {
+        return exit;
+    }
+
     let mut app = App::new();
     // Don't panic on Bevy system errors, just log them.
     app.set_error_handler(error);
 
     // Add Bevy plugins.
     app.insert_resource(DefaultOpaqueRendererMethod::deferred());
+    // Maps contain the classes of the prop manifest, which only exist once the manifest is read.
+    app.register_asset_source(
+        AssetSourceId::Default,
+        props::manifest::prop_class_asset_source(),
+    );
     app.add_plugins((
         DefaultPlugins
             .set(AssetPlugin {
@@ -74,13 +92,7 @@ fn main() -> AppExit {
             .set(ImagePlugin {
                 default_sampler: default_image_sampler_descriptor(),
             })
-            .set(GltfPlugin {
-                convert_coordinates: GltfConvertCoordinates {
-                    rotate_scene_entity: true,
-                    rotate_meshes: true,
-                },
-                ..default()
-            })
+            .set(gltf_plugin())
             .set(LogPlugin {
                 filter: format!(
                     concat!(
@@ -142,6 +154,7 @@ fn main() -> AppExit {
 
     // Add other plugins.
     app.add_plugins((
+        animation::plugin,
         asset_processing::plugin,
         asset_tracking::plugin,
         #[cfg(feature = "dev")]
@@ -152,6 +165,7 @@ fn main() -> AppExit {
         theme::plugin,
         ui_camera::plugin,
         hdr::plugin,
+        graphics::plugin,
         audio::plugin,
     ));
 
@@ -161,6 +175,17 @@ fn main() -> AppExit {
     app.run()
 }
 
+/// Loads glTF models the way our levels expect them. Tools that load the level headless use this as well.
+fn gltf_plugin() -> GltfPlugin {
+    GltfPlugin {
+        convert_coordinates: GltfConvertCoordinates {
+            rotate_scene_entity: true,
+            rotate_meshes: true,
+        },
+        ..default()
+    }
+}
+
 /// High-level groupings of systems for the app in the [`Update`] schedule.
 /// When adding a new variant, make sure to order it in the `configure_sets`
 /// call above.
@@ -5,11 +5,9 @@ use crate::{
     Pause,
     asset_tracking::LoadResource,
     menus::Menu,
-    theme::{palette::SCREEN_BACKGROUND, prelude::*},
-};
-use bevy::{
-    ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*,
+    theme::{navigation::back_just_pressed, palette::SCREEN_BACKGROUND, prelude::*},
 };
+use bevy::{ecs::spawn::SpawnIter, prelude::*, ui::Val::*};
 use bevy_seedling::sample::AudioSample;
 use bevy_seedling::sample::SamplePlayer;
 
@@ -17,7 +15,7 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Credits), spawn_credits_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Credits).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Credits).and(back_just_pressed)),
     );
 
     app.load_resource::<CreditsAssets>();
@@ -0,0 +1,163 @@
+//! The dialogue history, a scrollable backlog of everything said in conversations. Open it with H at any time during
+//! gameplay, including in the middle of a conversation, or from the pause menu.
+
+use std::any::Any as _;
+
+use bevy::{
+    input::{
+        common_conditions::input_just_pressed,
+        mouse::{MouseScrollUnit, MouseWheel},
+    },
+    prelude::*,
+    ui::Val::*,
+};
+
+use crate::{
+    gameplay::{
+        crosshair::CrosshairState,
+        player::{
+            dialogue::history::{DialogueHistory, DialogueHistoryKind},
+            input::BlocksInput,
+        },
+    },
+    menus::Menu,
+    screens::{
+        Screen,
+        gameplay::{pause, spawn_pause_overlay},
+    },
+    theme::{navigation::back_just_pressed, widget},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DialogueHistoryReturnMenu>();
+    app.add_systems(
+        Update,
+        (pause, spawn_pause_overlay, open_dialogue_history).run_if(
+            in_state(Screen::Gameplay)
+                .and(in_state(Menu::None))
+                .and(input_just_pressed(KeyCode::KeyH)),
+        ),
+    );
+    app.add_systems(OnEnter(Menu::DialogueHistory), spawn_dialogue_history_menu);
+    app.add_systems(OnExit(Menu::DialogueHistory), release_input);
+    app.add_systems(
+        Update,
+        (
+            scroll_dialogue_history.run_if(on_message::<MouseWheel>),
+            go_back.run_if(back_just_pressed.or(input_just_pressed(KeyCode::KeyH))),
+        )
+            .run_if(in_state(Menu::DialogueHistory)),
+    );
+}
+
+/// The menu to go back to when closing the dialogue history.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct DialogueHistoryReturnMenu(pub(crate) Menu);
+
+/// How far one line of a mouse wheel scrolls, in pixels.
+const SCROLL_LINE_HEIGHT: f32 = 24.0;
+
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct DialogueHistoryList;
+
+fn open_dialogue_history(
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut return_menu: ResMut<DialogueHistoryReturnMenu>,
+) {
+    return_menu.0 = Menu::None;
+    next_menu.set(Menu::DialogueHistory);
+}
+
+fn spawn_dialogue_history_menu(
+    mut commands: Commands,
+    history: Res<DialogueHistory>,
+    mut crosshair: Single<&mut CrosshairState>,
+    mut blocks_input: ResMut<BlocksInput>,
+) {
+    let entries: Vec<_> = history
+        .iter()
+        .map(|entry| match (entry.kind, &entry.speaker) {
+            (DialogueHistoryKind::ChosenOption, _) => format!("> {}", entry.text),
+            (DialogueHistoryKind::Line, Some(speaker)) => format!("{speaker}: {}", entry.text),
+            (DialogueHistoryKind::Line, None) => entry.text.clone(),
+        })
+        .collect();
+
+    commands
+        .spawn((
+            widget::ui_root("Dialogue History Screen"),
+            DespawnOnExit(Menu::DialogueHistory),
+            GlobalZIndex(2),
+        ))
+        .with_children(|parent| {
+            parent.spawn(widget::header("Dialogue History"));
+            parent
+                .spawn((
+                    Name::new("Dialogue History List"),
+                    DialogueHistoryList,
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Px(10.0),
+                        width: Px(800.0),
+                        max_height: Vh(60.0),
+                        overflow: Overflow::scroll_y(),
+                        ..default()
+                    },
+                    // Starts at the most recent line; scrolling clamps this to the end of the list.
+                    ScrollPosition(Vec2::new(0.0, f32::MAX)),
+                ))
+                .with_children(|list| {
+                    if entries.is_empty() {
+                        list.spawn(widget::label("Nothing has been said yet."));
+                    }
+                    for entry in entries {
+                        list.spawn(widget::label(entry));
+                    }
+                });
+            parent.spawn(widget::button("Back", go_back_on_click));
+        });
+    crosshair
+        .wants_free_cursor
+        .insert(spawn_dialogue_history_menu.type_id());
+    blocks_input.insert(spawn_dialogue_history_menu.type_id());
+}
+
+fn release_input(
+    mut crosshair: Single<&mut CrosshairState>,
+    mut blocks_input: ResMut<BlocksInput>,
+) {
+    crosshair
+        .wants_free_cursor
+        .remove(&spawn_dialogue_history_menu.type_id());
+    blocks_input.remove(&spawn_dialogue_history_menu.type_id());
+}
+
+fn scroll_dialogue_history(
+    mut mouse_wheel: MessageReader<MouseWheel>,
+    list: Single<(&mut ScrollPosition, &ComputedNode), With<DialogueHistoryList>>,
+) {
+    let (mut scroll_position, computed_node) = list.into_inner();
+    let max_scroll = ((computed_node.content_size().y - computed_node.size().y)
+        * computed_node.inverse_scale_factor())
+    .max(0.0);
+    for wheel in mouse_wheel.read() {
+        let delta = match wheel.unit {
+            MouseScrollUnit::Line => wheel.y * SCROLL_LINE_HEIGHT,
+            MouseScrollUnit::Pixel => wheel.y,
+        };
+        scroll_position.0.y = (scroll_position.0.y - delta).clamp(0.0, max_scroll);
+    }
+}
+
+fn go_back_on_click(
+    _on: On<Pointer<Click>>,
+    return_menu: Res<DialogueHistoryReturnMenu>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    next_menu.set(return_menu.0.clone());
+}
+
+fn go_back(return_menu: Res<DialogueHistoryReturnMenu>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(return_menu.0.clone());
+}
@@ -0,0 +1,616 @@
+//! The display settings screen, accessible from the settings screen.
+//!
+//! Natively, the window mode, monitor, resolution and refresh rate are chosen first and then applied together. Applying
+//! asks the player whether to keep the new settings and reverts them if they don't confirm in time, in case the
+//! monitor can't show them. Web builds can only toggle fullscreen, which is applied right away.
+
+#[cfg(not(target_family = "wasm"))]
+use std::time::Duration;
+
+#[cfg(not(target_family = "wasm"))]
+use bevy::window::{PrimaryMonitor, VideoModeSelection, WindowPosition};
+use bevy::{
+    prelude::*,
+    ui::Val::*,
+    window::{Monitor, MonitorSelection, PrimaryWindow, WindowMode},
+};
+
+use crate::{
+    Pause,
+    menus::Menu,
+    theme::{
+        navigation::back_just_pressed,
+        palette::{LABEL_TEXT, SCREEN_BACKGROUND},
+        prelude::*,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<PendingDisplaySettings>();
+    app.add_systems(
+        OnEnter(Menu::Display),
+        (read_display_settings, spawn_display_menu).chain(),
+    );
+    let wants_back = back_just_pressed;
+    // The prompt has to be answered before leaving.
+    #[cfg(not(target_family = "wasm"))]
+    let wants_back = wants_back.and(not(any_with_component::<KeepSettingsPrompt>));
+    app.add_systems(
+        Update,
+        (go_back.run_if(wants_back), update_display_labels).run_if(in_state(Menu::Display)),
+    );
+    #[cfg(target_family = "wasm")]
+    app.add_systems(
+        Update,
+        apply_display_settings
+            .run_if(in_state(Menu::Display).and(resource_changed::<PendingDisplaySettings>)),
+    );
+    #[cfg(not(target_family = "wasm"))]
+    {
+        app.add_systems(
+            Update,
+            tick_keep_settings_countdown.run_if(in_state(Menu::Display)),
+        );
+        app.add_systems(OnExit(Menu::Display), revert_unconfirmed_settings);
+    }
+}
+
+/// The display settings chosen in the menu, which are not necessarily applied yet.
+#[derive(Resource, Debug, Default, Reflect)]
+#[reflect(Resource)]
+struct PendingDisplaySettings {
+    mode: DisplayMode,
+    /// Index into the monitors as sorted by [`sorted_monitors`].
+    #[cfg(not(target_family = "wasm"))]
+    monitor: usize,
+    /// In physical pixels.
+    #[cfg(not(target_family = "wasm"))]
+    resolution: UVec2,
+    #[cfg(not(target_family = "wasm"))]
+    refresh_rate_millihertz: u32,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+enum DisplayMode {
+    #[default]
+    Windowed,
+    Borderless,
+    /// Exclusive fullscreen.
+    #[cfg(not(target_family = "wasm"))]
+    Fullscreen,
+}
+
+/// A row of the display settings. Marks the label showing its current value.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum DisplayOption {
+    Mode,
+    #[cfg(not(target_family = "wasm"))]
+    Monitor,
+    #[cfg(not(target_family = "wasm"))]
+    Resolution,
+    #[cfg(not(target_family = "wasm"))]
+    RefreshRate,
+}
+
+impl DisplayOption {
+    #[cfg(not(target_family = "wasm"))]
+    const ALL: &[Self] = &[
+        Self::Mode,
+        Self::Monitor,
+        Self::Resolution,
+        Self::RefreshRate,
+    ];
+    #[cfg(target_family = "wasm")]
+    const ALL: &[Self] = &[Self::Mode];
+
+    fn name(self) -> &'static str {
+        match self {
+            #[cfg(not(target_family = "wasm"))]
+            Self::Mode => "Window Mode",
+            #[cfg(target_family = "wasm")]
+            Self::Mode => "Fullscreen",
+            #[cfg(not(target_family = "wasm"))]
+            Self::Monitor => "Monitor",
+            #[cfg(not(target_family = "wasm"))]
+            Self::Resolution => "Resolution",
+            #[cfg(not(target_family = "wasm"))]
+            Self::RefreshRate => "Refresh Rate",
+        }
+    }
+
+    /// Whether the option matters for the chosen window mode. Borderless windows always use the desktop's resolution and
+    /// refresh rate, and windows the desktop's refresh rate.
+    #[cfg_attr(target_family = "wasm", allow(unused_variables))]
+    fn is_available(self, mode: DisplayMode) -> bool {
+        match self {
+            Self::Mode => true,
+            #[cfg(not(target_family = "wasm"))]
+            Self::Monitor => true,
+            #[cfg(not(target_family = "wasm"))]
+            Self::Resolution => mode != DisplayMode::Borderless,
+            #[cfg(not(target_family = "wasm"))]
+            Self::RefreshRate => mode == DisplayMode::Fullscreen,
+        }
+    }
+
+    #[cfg_attr(target_family = "wasm", allow(unused_variables))]
+    fn value(
+        self,
+        settings: &PendingDisplaySettings,
+        monitors: &Query<(Entity, &Monitor)>,
+    ) -> String {
+        if !self.is_available(settings.mode) {
+            return "Desktop".into();
+        }
+        match self {
+            #[cfg(not(target_family = "wasm"))]
+            Self::Mode => match settings.mode {
+                DisplayMode::Windowed => "Windowed".into(),
+                DisplayMode::Borderless => "Borderless".into(),
+                DisplayMode::Fullscreen => "Fullscreen".into(),
+            },
+            #[cfg(target_family = "wasm")]
+            Self::Mode => match settings.mode {
+                DisplayMode::Windowed => "Off".into(),
+                DisplayMode::Borderless => "On".into(),
+            },
+            #[cfg(not(target_family = "wasm"))]
+            Self::Monitor => sorted_monitors(monitors)
+                .get(settings.monitor)
+                .and_then(|(_, monitor)| monitor.name.clone())
+                .unwrap_or_else(|| format!("Monitor {}", settings.monitor + 1)),
+            #[cfg(not(target_family = "wasm"))]
+            Self::Resolution => format!("{}x{}", settings.resolution.x, settings.resolution.y),
+            #[cfg(not(target_family = "wasm"))]
+            Self::RefreshRate => {
+                format!("{:.0} Hz", settings.refresh_rate_millihertz as f32 / 1000.0)
+            }
+        }
+    }
+
+    /// Changes the option by one step, downwards if `raise` is false.
+    #[cfg_attr(target_family = "wasm", allow(unused_variables))]
+    fn step(
+        self,
+        settings: &mut PendingDisplaySettings,
+        monitors: &Query<(Entity, &Monitor)>,
+        raise: bool,
+    ) {
+        if !self.is_available(settings.mode) {
+            return;
+        }
+        match self {
+            #[cfg(not(target_family = "wasm"))]
+            Self::Mode => {
+                let modes = [
+                    DisplayMode::Windowed,
+                    DisplayMode::Borderless,
+                    DisplayMode::Fullscreen,
+                ];
+                settings.mode = step_through(&modes, &settings.mode, raise);
+                settings.clamp_to_monitor(monitors);
+            }
+            #[cfg(target_family = "wasm")]
+            Self::Mode => {
+                settings.mode = if raise {
+                    DisplayMode::Borderless
+                } else {
+                    DisplayMode::Windowed
+                };
+            }
+            #[cfg(not(target_family = "wasm"))]
+            Self::Monitor => {
+                let count = sorted_monitors(monitors).len();
+                settings.monitor = if raise {
+                    (settings.monitor + 1).min(count.saturating_sub(1))
+                } else {
+                    settings.monitor.saturating_sub(1)
+                };
+                settings.clamp_to_monitor(monitors);
+            }
+            #[cfg(not(target_family = "wasm"))]
+            Self::Resolution => {
+                let resolutions = settings.resolutions(monitors);
+                // Resolutions are sorted from largest to smallest.
+                settings.resolution = step_through(&resolutions, &settings.resolution, !raise);
+                settings.clamp_to_monitor(monitors);
+            }
+            #[cfg(not(target_family = "wasm"))]
+            Self::RefreshRate => {
+                let refresh_rates = settings.refresh_rates(monitors);
+                settings.refresh_rate_millihertz =
+                    step_through(&refresh_rates, &settings.refresh_rate_millihertz, raise);
+            }
+        }
+    }
+}
+
+/// Returns the value after `current` in `values`, or before it if `forward` is false. Stays at the ends.
+#[cfg(not(target_family = "wasm"))]
+fn step_through<T: PartialEq + Copy>(values: &[T], current: &T, forward: bool) -> T {
+    let Some(index) = values.iter().position(|value| value == current) else {
+        return values.first().copied().unwrap_or(*current);
+    };
+    let index = if forward {
+        (index + 1).min(values.len() - 1)
+    } else {
+        index.saturating_sub(1)
+    };
+    values[index]
+}
+
+/// The monitors from left to right, so that their order matches their arrangement.
+#[cfg(not(target_family = "wasm"))]
+fn sorted_monitors<'a>(monitors: &'a Query<(Entity, &Monitor)>) -> Vec<(Entity, &'a Monitor)> {
+    let mut sorted: Vec<_> = monitors.iter().collect();
+    sorted.sort_by_key(|(_, monitor)| (monitor.physical_position.x, monitor.physical_position.y));
+    sorted
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl PendingDisplaySettings {
+    /// The resolutions the selected monitor supports, from largest to smallest.
+    fn resolutions(&self, monitors: &Query<(Entity, &Monitor)>) -> Vec<UVec2> {
+        let Some((_, monitor)) = sorted_monitors(monitors).get(self.monitor).copied() else {
+            return vec![self.resolution];
+        };
+        let mut resolutions: Vec<_> = monitor
+            .video_modes
+            .iter()
+            .map(|mode| mode.physical_size)
+            .collect();
+        resolutions.sort_by_key(|size| std::cmp::Reverse((size.x, size.y)));
+        resolutions.dedup();
+        resolutions
+    }
+
+    /// The refresh rates the selected monitor supports at the selected resolution, from lowest to highest.
+    fn refresh_rates(&self, monitors: &Query<(Entity, &Monitor)>) -> Vec<u32> {
+        let Some((_, monitor)) = sorted_monitors(monitors).get(self.monitor).copied() else {
+            return vec![self.refresh_rate_millihertz];
+        };
+        let mut refresh_rates: Vec<_> = monitor
+            .video_modes
+            .iter()
+            .filter(|mode| mode.physical_size == self.resolution)
+            .map(|mode| mode.refresh_rate_millihertz)
+            .collect();
+        refresh_rates.sort();
+        refresh_rates.dedup();
+        refresh_rates
+    }
+
+    /// Picks a resolution and refresh rate the selected monitor supports, keeping the current ones if possible.
+    /// Only exclusive fullscreen is limited to the monitor's video modes, windows keep their size.
+    fn clamp_to_monitor(&mut self, monitors: &Query<(Entity, &Monitor)>) {
+        if self.mode != DisplayMode::Fullscreen {
+            return;
+        }
+        let resolutions = self.resolutions(monitors);
+        if !resolutions.contains(&self.resolution) {
+            if let Some(largest) = resolutions.first() {
+                self.resolution = *largest;
+            }
+        }
+        let refresh_rates = self.refresh_rates(monitors);
+        if !refresh_rates.contains(&self.refresh_rate_millihertz) {
+            if let Some(highest) = refresh_rates.last() {
+                self.refresh_rate_millihertz = *highest;
+            }
+        }
+    }
+}
+
+fn read_display_settings(
+    window: Single<&Window, With<PrimaryWindow>>,
+    #[cfg(not(target_family = "wasm"))] monitors: Query<(Entity, &Monitor)>,
+    #[cfg(not(target_family = "wasm"))] primary_monitor: Option<
+        Single<Entity, With<PrimaryMonitor>>,
+    >,
+    mut settings: ResMut<PendingDisplaySettings>,
+) {
+    settings.mode = match window.mode {
+        WindowMode::Windowed => DisplayMode::Windowed,
+        WindowMode::BorderlessFullscreen(_) => DisplayMode::Borderless,
+        #[cfg(not(target_family = "wasm"))]
+        WindowMode::Fullscreen(..) => DisplayMode::Fullscreen,
+        #[cfg(target_family = "wasm")]
+        WindowMode::Fullscreen(..) => DisplayMode::Borderless,
+    };
+    #[cfg(not(target_family = "wasm"))]
+    {
+        let monitor_entity = match window.mode {
+            WindowMode::BorderlessFullscreen(MonitorSelection::Entity(entity))
+            | WindowMode::Fullscreen(MonitorSelection::Entity(entity), _) => Some(entity),
+            _ => primary_monitor.map(|primary| *primary),
+        };
+        let sorted = sorted_monitors(&monitors);
+        settings.monitor = sorted
+            .iter()
+            .position(|(entity, _)| Some(*entity) == monitor_entity)
+            .unwrap_or(0);
+        settings.resolution = window.physical_size();
+        settings.refresh_rate_millihertz = match window.mode {
+            WindowMode::Fullscreen(_, VideoModeSelection::Specific(mode)) => {
+                mode.refresh_rate_millihertz
+            }
+            _ => sorted
+                .get(settings.monitor)
+                .and_then(|(_, monitor)| monitor.refresh_rate_millihertz)
+                .unwrap_or(60_000),
+        };
+        settings.clamp_to_monitor(&monitors);
+    }
+}
+
+fn spawn_display_menu(mut commands: Commands, paused: Res<State<Pause>>) {
+    let mut entity_commands = commands.spawn((
+        widget::ui_root("Display Screen"),
+        DespawnOnExit(Menu::Display),
+        GlobalZIndex(2),
+    ));
+    entity_commands.with_children(|parent| {
+        parent.spawn(widget::header("Display"));
+        parent
+            .spawn((
+                Name::new("Display Grid"),
+                Node {
+                    display: Display::Grid,
+                    row_gap: Px(10.0),
+                    column_gap: Px(30.0),
+                    grid_template_columns: RepeatedGridTrack::px(2, 400.0),
+                    ..default()
+                },
+            ))
+            .with_children(|grid| {
+                for option in DisplayOption::ALL.iter().copied() {
+                    grid.spawn((
+                        widget::label(option.name()),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        },
+                    ));
+                    grid.spawn(widget::plus_minus_bar(
+                        option,
+                        step_option(option, false),
+                        step_option(option, true),
+                    ));
+                }
+            });
+        #[cfg(not(target_family = "wasm"))]
+        parent.spawn(widget::button("Apply", apply_and_ask_to_keep));
+        parent.spawn(widget::button("Back", go_back_on_click));
+    });
+    if paused.get() == &Pause(false) {
+        entity_commands.insert(BackgroundColor(SCREEN_BACKGROUND));
+    }
+}
+
+fn step_option(
+    option: DisplayOption,
+    raise: bool,
+) -> impl FnMut(On<Pointer<Click>>, ResMut<PendingDisplaySettings>, Query<(Entity, &Monitor)>) {
+    move |_on, mut settings, monitors| option.step(&mut settings, &monitors, raise)
+}
+
+fn update_display_labels(
+    mut labels: Query<(&mut Text, &mut TextColor, &DisplayOption)>,
+    settings: Res<PendingDisplaySettings>,
+    monitors: Query<(Entity, &Monitor)>,
+) {
+    for (mut text, mut color, option) in &mut labels {
+        text.0 = option.value(&settings, &monitors);
+        // Greys out options that don't matter for the chosen window mode.
+        let alpha = if option.is_available(settings.mode) {
+            1.0
+        } else {
+            0.4
+        };
+        color.set_if_neq(TextColor(LABEL_TEXT.with_alpha(alpha)));
+    }
+}
+
+fn apply_display_settings(
+    settings: Res<PendingDisplaySettings>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+    #[cfg(not(target_family = "wasm"))] monitors: Query<(Entity, &Monitor)>,
+) {
+    #[cfg(target_family = "wasm")]
+    {
+        window.mode = match settings.mode {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+        };
+    }
+    #[cfg(not(target_family = "wasm"))]
+    {
+        let sorted = sorted_monitors(&monitors);
+        let monitor = sorted
+            .get(settings.monitor)
+            .map_or(MonitorSelection::Current, |(entity, _)| {
+                MonitorSelection::Entity(*entity)
+            });
+        window.mode = match settings.mode {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => WindowMode::BorderlessFullscreen(monitor),
+            DisplayMode::Fullscreen => {
+                let video_mode = sorted.get(settings.monitor).and_then(|(_, info)| {
+                    info.video_modes.iter().copied().find(|mode| {
+                        mode.physical_size == settings.resolution
+                            && mode.refresh_rate_millihertz == settings.refresh_rate_millihertz
+                    })
+                });
+                WindowMode::Fullscreen(
+                    monitor,
+                    video_mode.map_or(VideoModeSelection::Current, VideoModeSelection::Specific),
+                )
+            }
+        };
+        if settings.mode == DisplayMode::Windowed {
+            window
+                .resolution
+                .set_physical_resolution(settings.resolution.x, settings.resolution.y);
+            window.position = WindowPosition::Centered(monitor);
+        }
+    }
+}
+
+/// How long the player has to confirm new display settings before they are reverted.
+#[cfg(not(target_family = "wasm"))]
+const KEEP_SETTINGS_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Asks the player whether to keep the applied display settings. Holds the ones to revert to.
+#[cfg(not(target_family = "wasm"))]
+#[derive(Component, Debug)]
+struct KeepSettingsPrompt {
+    timer: Timer,
+    previous: PreviousDisplaySettings,
+}
+
+#[cfg(not(target_family = "wasm"))]
+#[derive(Debug, Clone)]
+struct PreviousDisplaySettings {
+    mode: WindowMode,
+    resolution: UVec2,
+    position: WindowPosition,
+}
+
+#[cfg(not(target_family = "wasm"))]
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct KeepSettingsCountdown;
+
+#[cfg(not(target_family = "wasm"))]
+fn apply_and_ask_to_keep(
+    _on: On<Pointer<Click>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    existing_prompt: Query<(), With<KeepSettingsPrompt>>,
+    mut commands: Commands,
+) {
+    if !existing_prompt.is_empty() {
+        return;
+    }
+    let previous = PreviousDisplaySettings {
+        mode: window.mode,
+        resolution: window.physical_size(),
+        position: window.position,
+    };
+    commands.run_system_cached(apply_display_settings);
+    commands.spawn((
+        widget::ui_root("Keep Display Settings Prompt"),
+        KeepSettingsPrompt {
+            timer: Timer::new(KEEP_SETTINGS_TIMEOUT, TimerMode::Once),
+            previous,
+        },
+        BackgroundColor(SCREEN_BACKGROUND.with_alpha(0.95)),
+        GlobalZIndex(3),
+        DespawnOnExit(Menu::Display),
+        children![
+            widget::header("Keep these settings?"),
+            (widget::label(""), KeepSettingsCountdown),
+            widget::button("Keep", keep_settings),
+            widget::button("Revert", revert_settings),
+        ],
+    ));
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn tick_keep_settings_countdown(
+    prompt: Option<Single<(Entity, &mut KeepSettingsPrompt)>>,
+    mut countdown: Single<&mut Text, With<KeepSettingsCountdown>>,
+    // The game may be paused while the menu is open.
+    time: Res<Time<Real>>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+    mut commands: Commands,
+) {
+    let Some(prompt) = prompt else {
+        return;
+    };
+    let (entity, mut prompt) = prompt.into_inner();
+    prompt.timer.tick(time.delta());
+    if prompt.timer.is_finished() {
+        restore(&mut window, &prompt.previous);
+        commands.entity(entity).despawn();
+        return;
+    }
+    countdown.0 = format!(
+        "Reverting in {} seconds",
+        prompt.timer.remaining_secs().ceil()
+    );
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn keep_settings(
+    _on: On<Pointer<Click>>,
+    prompt: Single<Entity, With<KeepSettingsPrompt>>,
+    mut commands: Commands,
+) {
+    commands.entity(*prompt).despawn();
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn revert_settings(
+    _on: On<Pointer<Click>>,
+    prompt: Single<(Entity, &KeepSettingsPrompt)>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+    mut commands: Commands,
+) {
+    let (entity, prompt) = prompt.into_inner();
+    restore(&mut window, &prompt.previous);
+    commands.entity(entity).despawn();
+}
+
+/// Leaving the menu doesn't count as confirming.
+#[cfg(not(target_family = "wasm"))]
+fn revert_unconfirmed_settings(
+    prompt: Option<Single<&KeepSettingsPrompt>>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+) {
+    if let Some(prompt) = prompt {
+        restore(&mut window, &prompt.previous);
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn restore(window: &mut Window, previous: &PreviousDisplaySettings) {
+    window.mode = previous.mode;
+    window
+        .resolution
+        .set_physical_resolution(previous.resolution.x, previous.resolution.y);
+    window.position = previous.position;
+}
+
+fn go_back_on_click(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+#[cfg(all(test, not(target_family = "wasm")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_through_moves_to_neighbors() {
+        let values = [1, 2, 3];
+        assert_eq!(step_through(&values, &2, true), 3);
+        assert_eq!(step_through(&values, &2, false), 1);
+    }
+
+    #[test]
+    fn step_through_stays_at_the_ends() {
+        let values = [1, 2, 3];
+        assert_eq!(step_through(&values, &3, true), 3);
+        assert_eq!(step_through(&values, &1, false), 1);
+    }
+
+    #[test]
+    fn step_through_starts_over_from_unknown_values() {
+        assert_eq!(step_through(&[1, 2, 3], &5, false), 1);
+        assert_eq!(step_through(&[], &5, true), 5);
+    }
+}
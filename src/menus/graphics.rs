@@ -0,0 +1,233 @@
+//! The graphics settings screen, accessible from the settings screen. Choosing a preset sets all options at once;
+//! changing an option individually makes the preset "Custom".
+
+use bevy::{prelude::*, ui::Val::*};
+
+use crate::{
+    Pause,
+    graphics::{AntiAliasing, GraphicsPreset, GraphicsSettings},
+    menus::Menu,
+    theme::{navigation::back_just_pressed, palette::SCREEN_BACKGROUND, prelude::*},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Graphics), spawn_graphics_menu);
+    app.add_systems(
+        Update,
+        (go_back.run_if(back_just_pressed), update_graphics_labels)
+            .run_if(in_state(Menu::Graphics)),
+    );
+}
+
+/// A row of the graphics settings. Marks the label showing its current value.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum GraphicsOption {
+    Preset,
+    AntiAliasing,
+    Ssao,
+    Bloom,
+    ShadowMapSize,
+    ShadowLightBudget,
+    ParticleDensity,
+    RenderScale,
+    DynamicResolution,
+}
+
+impl GraphicsOption {
+    const ALL: [Self; 9] = [
+        Self::Preset,
+        Self::AntiAliasing,
+        Self::Ssao,
+        Self::Bloom,
+        Self::ShadowMapSize,
+        Self::ShadowLightBudget,
+        Self::ParticleDensity,
+        Self::RenderScale,
+        Self::DynamicResolution,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Preset => "Preset",
+            Self::AntiAliasing => "Anti-Aliasing",
+            Self::Ssao => "Ambient Occlusion",
+            Self::Bloom => "Bloom",
+            Self::ShadowMapSize => "Shadow Resolution",
+            Self::ShadowLightBudget => "Shadowed Lights",
+            Self::ParticleDensity => "Particle Density",
+            Self::RenderScale => "Render Scale",
+            Self::DynamicResolution => "Dynamic Resolution",
+        }
+    }
+
+    fn value(self, settings: &GraphicsSettings) -> String {
+        match self {
+            Self::Preset => format!("{:?}", settings.preset()),
+            Self::AntiAliasing => match settings.anti_aliasing {
+                AntiAliasing::Off => "Off".into(),
+                AntiAliasing::Fxaa => "FXAA".into(),
+                AntiAliasing::Taa => "TAA".into(),
+            },
+            Self::Ssao if !cfg!(feature = "native") => "Unavailable".into(),
+            Self::Ssao => on_off(settings.ssao).into(),
+            Self::Bloom => on_off(settings.bloom).into(),
+            Self::ShadowMapSize => format!("{}", settings.shadow_map_size),
+            Self::ShadowLightBudget => format!("{}", settings.shadow_light_budget),
+            Self::ParticleDensity => format!("{}%", settings.particle_density),
+            Self::RenderScale => format!("{}%", settings.render_scale),
+            Self::DynamicResolution => match settings.dynamic_resolution {
+                Some(target_fps) => format!("{target_fps} FPS"),
+                None => "Off".into(),
+            },
+        }
+    }
+
+    /// Changes the option by one step, downwards if `raise` is false.
+    fn step(self, settings: &mut GraphicsSettings, raise: bool) {
+        match self {
+            Self::Preset => {
+                let presets = [
+                    GraphicsPreset::Low,
+                    GraphicsPreset::Medium,
+                    GraphicsPreset::High,
+                ];
+                let preset = match (settings.preset(), raise) {
+                    // Custom settings sit between the presets, so step to the closest one in that direction.
+                    (GraphicsPreset::Custom, true) => GraphicsPreset::High,
+                    (GraphicsPreset::Custom, false) => GraphicsPreset::Low,
+                    (preset, raise) => {
+                        let index = presets.iter().position(|p| *p == preset).unwrap_or(0);
+                        let index = if raise {
+                            (index + 1).min(presets.len() - 1)
+                        } else {
+                            index.saturating_sub(1)
+                        };
+                        presets[index]
+                    }
+                };
+                settings.apply_preset(preset);
+            }
+            Self::AntiAliasing => {
+                settings.anti_aliasing = match (settings.anti_aliasing, raise) {
+                    (AntiAliasing::Off, true) | (AntiAliasing::Taa, false) => AntiAliasing::Fxaa,
+                    (AntiAliasing::Fxaa, true) | (AntiAliasing::Taa, true) => AntiAliasing::Taa,
+                    (AntiAliasing::Off, false) | (AntiAliasing::Fxaa, false) => AntiAliasing::Off,
+                };
+            }
+            Self::Ssao => settings.ssao = raise,
+            Self::Bloom => settings.bloom = raise,
+            Self::ShadowMapSize => {
+                settings.shadow_map_size = if raise {
+                    (settings.shadow_map_size * 2).min(4096)
+                } else {
+                    (settings.shadow_map_size / 2).max(512)
+                };
+            }
+            Self::ShadowLightBudget => {
+                settings.shadow_light_budget = if raise {
+                    (settings.shadow_light_budget + 1).min(16)
+                } else {
+                    settings.shadow_light_budget.saturating_sub(1)
+                };
+            }
+            Self::ParticleDensity => {
+                settings.particle_density = if raise {
+                    (settings.particle_density + 25).min(100)
+                } else {
+                    settings.particle_density.saturating_sub(25).max(25)
+                };
+            }
+            Self::RenderScale => {
+                settings.render_scale = if raise {
+                    (settings.render_scale + 5).min(100)
+                } else {
+                    settings.render_scale.saturating_sub(5).max(50)
+                };
+            }
+            Self::DynamicResolution => {
+                let targets = [None, Some(30), Some(60), Some(90), Some(120), Some(144)];
+                let index = targets
+                    .iter()
+                    .position(|target| *target == settings.dynamic_resolution)
+                    .unwrap_or(0);
+                let index = if raise {
+                    (index + 1).min(targets.len() - 1)
+                } else {
+                    index.saturating_sub(1)
+                };
+                settings.dynamic_resolution = targets[index];
+            }
+        }
+    }
+}
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled { "On" } else { "Off" }
+}
+
+fn spawn_graphics_menu(mut commands: Commands, paused: Res<State<Pause>>) {
+    let mut entity_commands = commands.spawn((
+        widget::ui_root("Graphics Screen"),
+        DespawnOnExit(Menu::Graphics),
+        GlobalZIndex(2),
+    ));
+    entity_commands.with_children(|parent| {
+        parent.spawn(widget::header("Graphics"));
+        parent
+            .spawn((
+                Name::new("Graphics Grid"),
+                Node {
+                    display: Display::Grid,
+                    row_gap: Px(10.0),
+                    column_gap: Px(30.0),
+                    grid_template_columns: RepeatedGridTrack::px(2, 400.0),
+                    ..default()
+                },
+            ))
+            .with_children(|grid| {
+                for option in GraphicsOption::ALL {
+                    grid.spawn((
+                        widget::label(option.name()),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        },
+                    ));
+                    grid.spawn(widget::plus_minus_bar(
+                        option,
+                        step_option(option, false),
+                        step_option(option, true),
+                    ));
+                }
+            });
+        parent.spawn(widget::button("Back", go_back_on_click));
+    });
+    if paused.get() == &Pause(false) {
+        entity_commands.insert(BackgroundColor(SCREEN_BACKGROUND));
+    }
+}
+
+fn step_option(
+    option: GraphicsOption,
+    raise: bool,
+) -> impl FnMut(On<Pointer<Click>>, ResMut<GraphicsSettings>) {
+    move |_on, mut settings| option.step(&mut settings, raise)
+}
+
+fn update_graphics_labels(
+    mut labels: Query<(&mut Text, &GraphicsOption)>,
+    settings: Res<GraphicsSettings>,
+) {
+    for (mut text, option) in &mut labels {
+        text.0 = option.value(&settings);
+    }
+}
+
+fn go_back_on_click(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
@@ -0,0 +1,65 @@
+//! The journal accessible from the pause menu. Lists the documents the player has read.
+
+use bevy::{prelude::*, ui::Val::*};
+
+use crate::{
+    gameplay::readable::ReadDocuments,
+    menus::Menu,
+    theme::{navigation::back_just_pressed, widget},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Journal), spawn_journal_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Journal).and(back_just_pressed)),
+    );
+}
+
+fn spawn_journal_menu(mut commands: Commands, read_documents: Res<ReadDocuments>) {
+    let entries: Vec<_> = read_documents
+        .iter()
+        .map(|document| {
+            let pages = document.pages.len();
+            let unit = if pages == 1 { "page" } else { "pages" };
+            format!("{} ({pages} {unit})", document.title)
+        })
+        .collect();
+
+    commands
+        .spawn((
+            widget::ui_root("Journal Screen"),
+            DespawnOnExit(Menu::Journal),
+            GlobalZIndex(2),
+        ))
+        .with_children(|parent| {
+            parent.spawn(widget::header("Journal"));
+            parent
+                .spawn((
+                    Name::new("Document List"),
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Px(10.0),
+                        max_width: Px(800.0),
+                        ..default()
+                    },
+                ))
+                .with_children(|list| {
+                    if entries.is_empty() {
+                        list.spawn(widget::label("You haven't read anything yet."));
+                    }
+                    for entry in entries {
+                        list.spawn(widget::label(entry));
+                    }
+                });
+            parent.spawn(widget::button("Back", go_back_on_click));
+        });
+}
+
+fn go_back_on_click(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Pause);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Pause);
+}
@@ -1,8 +1,13 @@
 //! The game's main screen states and transitions between them.
 
 mod credits;
+mod dialogue_history;
+mod display;
+mod graphics;
+mod journal;
 mod main;
-mod pause;
+mod objectives;
+pub(crate) mod pause;
 mod settings;
 
 use bevy::prelude::*;
@@ -12,7 +17,12 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_plugins((
         credits::plugin,
+        dialogue_history::plugin,
+        display::plugin,
+        graphics::plugin,
+        journal::plugin,
         main::plugin,
+        objectives::plugin,
         settings::plugin,
         pause::plugin,
     ));
@@ -28,4 +38,9 @@ pub(crate) enum Menu {
     Credits,
     Settings,
     Pause,
+    Objectives,
+    Journal,
+    DialogueHistory,
+    Graphics,
+    Display,
 }
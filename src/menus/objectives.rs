@@ -0,0 +1,80 @@
+//! The objectives screen accessible from the pause menu.
+
+use bevy::{prelude::*, ui::Val::*};
+
+use crate::{
+    gameplay::objectives::{ObjectiveState, Objectives},
+    menus::Menu,
+    theme::{navigation::back_just_pressed, widget},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Objectives), spawn_objectives_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Objectives).and(back_just_pressed)),
+    );
+}
+
+fn spawn_objectives_menu(mut commands: Commands, objectives: Res<Objectives>) {
+    let revealed: Vec<_> = objectives
+        .iter()
+        .filter(|objective| objective.is_revealed())
+        .map(|objective| {
+            let state = match objective.state {
+                ObjectiveState::Complete => "[x]",
+                ObjectiveState::Failed => "[failed]",
+                ObjectiveState::Active | ObjectiveState::Inactive => "[ ]",
+            };
+            let optional = if objective.is_optional() {
+                " (optional)"
+            } else {
+                ""
+            };
+            (
+                format!("{state} {}{optional}", objective.title()),
+                objective.description().to_string(),
+            )
+        })
+        .collect();
+
+    commands
+        .spawn((
+            widget::ui_root("Objectives Screen"),
+            DespawnOnExit(Menu::Objectives),
+            GlobalZIndex(2),
+        ))
+        .with_children(|parent| {
+            parent.spawn(widget::header("Objectives"));
+            parent
+                .spawn((
+                    Name::new("Objective List"),
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Px(10.0),
+                        max_width: Px(800.0),
+                        ..default()
+                    },
+                ))
+                .with_children(|list| {
+                    if revealed.is_empty() {
+                        list.spawn(widget::label("No objectives yet."));
+                    }
+                    for (title, description) in revealed {
+                        list.spawn(widget::label(title));
+                        if !description.is_empty() {
+                            list.spawn(widget::label_small(description));
+                        }
+                    }
+                });
+            parent.spawn(widget::button("Back", go_back_on_click));
+        });
+}
+
+fn go_back_on_click(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Pause);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Pause);
+}
@@ -4,17 +4,17 @@ use std::any::Any as _;
 
 use crate::{
     gameplay::{crosshair::CrosshairState, player::input::BlocksInput},
-    menus::Menu,
+    menus::{Menu, dialogue_history::DialogueHistoryReturnMenu},
     screens::Screen,
-    theme::widget,
+    theme::{navigation::back_just_pressed, widget},
 };
-use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Pause), spawn_pause_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Pause).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Pause).and(back_just_pressed)),
     );
 }
 
@@ -31,6 +31,9 @@ fn spawn_pause_menu(
         children![
             widget::header("Game paused"),
             widget::button("Continue", close_menu),
+            widget::button("Objectives", open_objectives_menu),
+            widget::button("Journal", open_journal_menu),
+            widget::button("Dialogue History", open_dialogue_history_menu),
             widget::button("Settings", open_settings_menu),
             widget::button("Quit to title", quit_to_title),
         ],
@@ -42,6 +45,23 @@ fn spawn_pause_menu(
     time.pause();
 }
 
+fn open_objectives_menu(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Objectives);
+}
+
+fn open_journal_menu(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Journal);
+}
+
+fn open_dialogue_history_menu(
+    _on: On<Pointer<Click>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut return_menu: ResMut<DialogueHistoryReturnMenu>,
+) {
+    return_menu.0 = Menu::Pause;
+    next_menu.set(Menu::DialogueHistory);
+}
+
 fn open_settings_menu(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Settings);
 }
@@ -76,7 +96,8 @@ fn quit_to_title(
     time.unpause();
 }
 
-fn go_back(
+/// Closes the pause menu and resumes the game. Also used by the `jam/set_pause` BRP method.
+pub(crate) fn go_back(
     mut next_menu: ResMut<NextState<Menu>>,
     mut crosshair: Single<&mut CrosshairState>,
     mut time: ResMut<Time<Virtual>>,
@@ -3,17 +3,17 @@
 //! For 3D, we'd also place the camera sensitivity and FOV here.
 
 use bevy::window::PresentMode;
-use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+use bevy::{prelude::*, ui::Val::*};
 use bevy_framepace::{FramepaceSettings, Limiter};
 use bevy_seedling::prelude::*;
 
 use crate::{
     Pause,
-    audio::{DEFAULT_MAIN_VOLUME, perceptual::PerceptualVolumeConverter},
-    gameplay::player::camera::{CameraSensitivity, WorldModelFov},
+    audio::{DEFAULT_MAIN_VOLUME, caption::CaptionSettings, perceptual::PerceptualVolumeConverter},
+    gameplay::player::camera::{CameraSensitivity, ReducedMotion, WorldModelFov},
     menus::Menu,
     screens::Screen,
-    theme::{palette::SCREEN_BACKGROUND, prelude::*},
+    theme::{navigation::back_just_pressed, palette::SCREEN_BACKGROUND, prelude::*},
 };
 
 pub(super) fn plugin(app: &mut App) {
@@ -23,7 +23,7 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Settings).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Settings).and(back_just_pressed)),
     );
 
     app.add_systems(
@@ -33,6 +33,8 @@ pub(super) fn plugin(app: &mut App) {
             update_volume_label,
             update_camera_sensitivity_label,
             update_camera_fov_label,
+            update_reduced_motion_label,
+            update_caption_size_label,
             update_vsync.run_if(resource_exists_and_changed::<VsyncSetting>),
             update_vsync_label,
             update_fps_limiter.run_if(resource_exists_and_changed::<FpsLimiterSettings>),
@@ -91,6 +93,28 @@ fn spawn_settings_menu(mut commands: Commands, paused: Res<State<Pause>>) {
                         }
                     ),
                     widget::plus_minus_bar(CameraFovLabel, lower_camera_fov, raise_camera_fov),
+                    // Reduced Motion
+                    (
+                        widget::label("Reduced Motion"),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(
+                        ReducedMotionLabel,
+                        disable_reduced_motion,
+                        enable_reduced_motion
+                    ),
+                    // Captions
+                    (
+                        widget::label("Captions"),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(CaptionSizeLabel, shrink_captions, grow_captions),
                     // VSync
                     (
                         widget::label("VSync"),
@@ -128,6 +152,8 @@ fn spawn_settings_menu(mut commands: Commands, paused: Res<State<Pause>>) {
                     ),
                 ],
             ),
+            widget::button("Graphics", open_graphics_menu),
+            widget::button("Display", open_display_menu),
             widget::button("Back", go_back_on_click),
         ],
     ));
@@ -248,6 +274,48 @@ fn update_camera_fov_label(
     label.0 = format!("{:.1}", camera_fov.0);
 }
 
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ReducedMotionLabel;
+
+fn enable_reduced_motion(_on: On<Pointer<Click>>, mut reduced_motion: ResMut<ReducedMotion>) {
+    reduced_motion.0 = true;
+}
+
+fn disable_reduced_motion(_on: On<Pointer<Click>>, mut reduced_motion: ResMut<ReducedMotion>) {
+    reduced_motion.0 = false;
+}
+
+fn update_reduced_motion_label(
+    mut label: Single<&mut Text, With<ReducedMotionLabel>>,
+    reduced_motion: Res<ReducedMotion>,
+) {
+    label.0 = if reduced_motion.0 {
+        "On".into()
+    } else {
+        "Off".into()
+    };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CaptionSizeLabel;
+
+fn shrink_captions(_on: On<Pointer<Click>>, mut settings: ResMut<CaptionSettings>) {
+    settings.size = settings.size.smaller();
+}
+
+fn grow_captions(_on: On<Pointer<Click>>, mut settings: ResMut<CaptionSettings>) {
+    settings.size = settings.size.larger();
+}
+
+fn update_caption_size_label(
+    mut label: Single<&mut Text, With<CaptionSizeLabel>>,
+    settings: Res<CaptionSettings>,
+) {
+    label.0 = settings.size.label().into();
+}
+
 #[derive(Resource, Reflect, Debug)]
 struct VsyncSetting(bool);
 
@@ -360,6 +428,14 @@ fn update_fps_limiter_target_label(
     label.0 = format!("{}", settings.target_fps);
 }
 
+fn open_graphics_menu(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Graphics);
+}
+
+fn open_display_menu(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Display);
+}
+
 fn go_back_on_click(
     _on: On<Pointer<Click>>,
     screen: Res<State<Screen>>,
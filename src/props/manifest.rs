@@ -0,0 +1,391 @@
+//! Props that don't need any special logic are listed in `assets/generic.props.ron` instead of being written by hand.
+//! The manifest is loaded at runtime, so adding a prop doesn't need a rebuild.
+//!
+//! Every entry has a classname of its own, which is what the exported FGD lists and what maps contain. Since
+//! `bevy_trenchbroom` only knows classes that are Rust types, maps are read through [`prop_class_asset_source`], which
+//! turns each entity of a manifest class into a [`GenericProp`] of that class before the map loader sees it.
+
+use std::path::Path;
+
+use avian_pickup::prop::PreferredPickupRotation;
+use avian3d::prelude::*;
+use bevy::{
+    asset::{
+        AssetLoader, LoadContext,
+        io::{
+            AssetReader, AssetReaderError, AssetSource, AssetSourceBuilder, ErasedAssetReader,
+            PathStream, Reader, VecReader,
+        },
+    },
+    ecs::system::SystemParam,
+    platform::collections::HashSet,
+    prelude::*,
+};
+use bevy_trenchbroom::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    asset_tracking::LoadResource as _,
+    props::setup::{dynamic_scene_bundle, static_scene_bundle},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<PropManifest>();
+    app.init_asset_loader::<PropManifestLoader>();
+    app.load_resource::<PropManifestAssets>();
+    app.add_observer(setup_generic_prop);
+}
+
+/// The path of the prop manifest, relative to `assets`.
+pub(crate) const PROP_MANIFEST_PATH: &str = "generic.props.ron";
+
+/// A prop from the prop manifest. Maps place the entry's own class, see [`expand_prop_classes`].
+#[point_class(base(Transform, Visibility), classname("generic_prop"))]
+#[derive(Default)]
+pub(crate) struct GenericProp {
+    /// The `classname` of an entry in `assets/generic.props.ron`.
+    #[class(must_set)]
+    pub(crate) kind: String,
+}
+
+/// Every prop listed in the prop manifest.
+#[derive(Asset, TypePath, Debug, Clone)]
+pub(crate) struct PropManifest(Vec<PropDefinition>);
+
+impl PropManifest {
+    /// Parses and validates the contents of a prop manifest. The models are not loaded.
+    pub(crate) fn from_ron(ron: &str) -> Result<Self, String> {
+        let props: Vec<PropDefinition> = ron::from_str(ron).map_err(|error| error.to_string())?;
+        let mut classnames = HashSet::new();
+        for prop in &props {
+            prop.validate()?;
+            if !classnames.insert(prop.classname.as_str()) {
+                return Err(format!(
+                    "The classname \"{}\" is used more than once",
+                    prop.classname
+                ));
+            }
+        }
+        Ok(Self(props))
+    }
+
+    pub(crate) fn get(&self, classname: &str) -> Option<&PropDefinition> {
+        self.0.iter().find(|prop| prop.classname == classname)
+    }
+
+    #[cfg(any(feature = "dev", feature = "native"))]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &PropDefinition> {
+        self.0.iter()
+    }
+}
+
+/// How a prop from the manifest is set up when it is spawned.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct PropDefinition {
+    pub(crate) classname: String,
+    /// The path of the model, relative to `assets`.
+    pub(crate) model: String,
+    pub(crate) physics: PropPhysics,
+    #[serde(default)]
+    pub(crate) collider: PropCollider,
+    /// Density in kg/m^3. Only used for dynamic props.
+    #[serde(default = "default_density")]
+    pub(crate) density: f32,
+    /// Yaw, pitch and roll in degrees.
+    #[serde(default)]
+    pub(crate) pickup_rotation: Option<(f32, f32, f32)>,
+    /// The scene of the model, loaded together with the manifest.
+    #[serde(skip)]
+    scene: Handle<Scene>,
+}
+
+impl PropDefinition {
+    fn validate(&self) -> Result<(), String> {
+        let classname = &self.classname;
+        let is_identifier = classname
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase())
+            && classname
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !is_identifier {
+            return Err(format!(
+                "\"{classname}\" is not a valid classname, use lowercase snake_case"
+            ));
+        }
+        if classname == GenericProp::CLASS_INFO.name {
+            return Err(format!(
+                "\"{classname}\" is the class all manifest props are loaded as, use another classname"
+            ));
+        }
+        if self.physics == PropPhysics::Dynamic && self.collider == PropCollider::Trimesh {
+            return Err(format!(
+                "\"{classname}\" is dynamic, so it cannot use a trimesh collider"
+            ));
+        }
+        if self.density.is_nan() || self.density <= 0.0 {
+            return Err(format!("The density of \"{classname}\" must be positive"));
+        }
+        Ok(())
+    }
+}
+
+fn default_density() -> f32 {
+    // About the density of oak wood (600-800 kg/m^3)
+    800.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub(crate) enum PropPhysics {
+    /// Unmovable terrain.
+    Static,
+    /// Influenced by physics and can be picked up.
+    Dynamic,
+    /// A decoration without a collider.
+    Nonphysical,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub(crate) enum PropCollider {
+    #[default]
+    ConvexHull,
+    ConvexDecomposition,
+    Trimesh,
+}
+
+impl PropCollider {
+    fn constructor(self) -> ColliderConstructor {
+        match self {
+            Self::ConvexHull => ColliderConstructor::ConvexHullFromMesh,
+            Self::ConvexDecomposition => ColliderConstructor::ConvexDecompositionFromMesh,
+            Self::Trimesh => ColliderConstructor::TrimeshFromMesh,
+        }
+    }
+}
+
+/// Preloads the prop manifest together with all of its models.
+#[derive(Resource, Asset, Clone, TypePath)]
+pub(crate) struct PropManifestAssets {
+    #[dependency]
+    manifest: Handle<PropManifest>,
+}
+
+impl FromWorld for PropManifestAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            manifest: assets.load(PROP_MANIFEST_PATH),
+        }
+    }
+}
+
+impl PropManifestAssets {
+    /// The manifest as it is currently loaded, e.g. after it was hot reloaded.
+    pub(crate) fn get<'a>(&self, manifests: &'a Assets<PropManifest>) -> Option<&'a PropManifest> {
+        manifests.get(&self.manifest)
+    }
+}
+
+/// The loaded prop manifest, for systems that want to look up props.
+#[derive(SystemParam)]
+pub(crate) struct LoadedPropManifest<'w> {
+    assets: Option<Res<'w, PropManifestAssets>>,
+    manifests: Res<'w, Assets<PropManifest>>,
+}
+
+impl LoadedPropManifest<'_> {
+    pub(crate) fn get(&self) -> Option<&PropManifest> {
+        self.assets.as_ref()?.get(&self.manifests)
+    }
+}
+
+#[derive(Default, TypePath)]
+struct PropManifestLoader;
+
+impl AssetLoader for PropManifestLoader {
+    type Asset = PropManifest;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut manifest = PropManifest::from_ron(std::str::from_utf8(&bytes)?)?;
+        for prop in &mut manifest.0 {
+            prop.scene = load_context.load(format!("{}#Scene0", prop.model));
+        }
+        Ok(manifest)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["props.ron"]
+    }
+}
+
+fn setup_generic_prop(
+    add: On<Add, GenericProp>,
+    props: Query<&GenericProp>,
+    manifest: LoadedPropManifest,
+    mut commands: Commands,
+) -> Result {
+    let classname = &props.get(add.entity)?.kind;
+    let manifest = manifest.get().ok_or("The prop manifest is not loaded")?;
+    let definition = manifest.get(classname).ok_or_else(|| {
+        format!("There is no prop with the classname \"{classname}\" in {PROP_MANIFEST_PATH}")
+    })?;
+    let mut entity = commands.entity(add.entity);
+    // Named after the class the map placed, not after `GenericProp`.
+    entity.insert(Name::new(classname.clone()));
+    let scene = definition.scene.clone();
+    let constructor = definition.collider.constructor();
+    match definition.physics {
+        PropPhysics::Static => {
+            entity.insert(static_scene_bundle(scene, constructor));
+        }
+        PropPhysics::Dynamic => {
+            entity.insert(dynamic_scene_bundle(scene, constructor, definition.density));
+        }
+        PropPhysics::Nonphysical => {
+            entity.insert(SceneRoot(scene));
+        }
+    }
+    if let Some((yaw, pitch, roll)) = definition.pickup_rotation {
+        let rotation = Quat::from_euler(
+            EulerRot::YXZ,
+            yaw.to_radians(),
+            pitch.to_radians(),
+            roll.to_radians(),
+        );
+        entity.insert(PreferredPickupRotation(rotation));
+    }
+    Ok(())
+}
+
+/// The default asset source, reading `.map` files through [`expand_prop_classes`].
+/// Has to be registered before the `AssetPlugin`.
+pub(crate) fn prop_class_asset_source() -> AssetSourceBuilder {
+    let mut default_reader = AssetSource::get_default_reader("assets".to_string());
+    AssetSourceBuilder::platform_default("assets", None).with_reader(move || {
+        Box::new(PropClassMapReader {
+            inner: default_reader(),
+        })
+    })
+}
+
+/// Reads assets like the default reader, but turns the manifest classes in maps into [`GenericProp`]s.
+/// The manifest is read again for every map, so that maps loaded after changing it see the new classes.
+struct PropClassMapReader {
+    inner: Box<dyn ErasedAssetReader>,
+}
+
+impl AssetReader for PropClassMapReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        let mut reader = self.inner.read(path).await?;
+        if path.extension().is_none_or(|extension| extension != "map") {
+            return Ok(reader);
+        }
+        let map = read_to_string(&mut reader).await?;
+        let mut manifest_reader = self.inner.read(Path::new(PROP_MANIFEST_PATH)).await?;
+        let manifest = read_to_string(&mut manifest_reader).await?;
+        let map = match PropManifest::from_ron(&manifest) {
+            Ok(manifest) => expand_prop_classes(&map, &manifest),
+            Err(error) => {
+                error!(
+                    "{PROP_MANIFEST_PATH} is invalid, loading the map without its props: {error}"
+                );
+                map
+            }
+        };
+        let reader: Box<dyn Reader + 'a> = Box::new(VecReader::new(map.into_bytes()));
+        Ok(reader)
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        self.inner.read_meta(path).await
+    }
+
+    async fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<PathStream>, AssetReaderError> {
+        self.inner.read_directory(path).await
+    }
+
+    async fn is_directory<'a>(&'a self, path: &'a Path) -> Result<bool, AssetReaderError> {
+        self.inner.is_directory(path).await
+    }
+}
+
+async fn read_to_string(reader: &mut dyn Reader) -> std::io::Result<String> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).await?;
+    String::from_utf8(bytes)
+        .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+}
+
+/// Replaces the classname of every entity whose class is listed in the manifest with [`GenericProp`],
+/// with the `kind` set to the listed class.
+fn expand_prop_classes(map: &str, manifest: &PropManifest) -> String {
+    let mut expanded = String::with_capacity(map.len());
+    for line in map.split_inclusive('\n') {
+        let content = line.trim_end();
+        let ending = &line[content.len()..];
+        let classname = content
+            .trim_start()
+            .strip_prefix("\"classname\" \"")
+            .and_then(|rest| rest.strip_suffix('"'))
+            .filter(|classname| manifest.get(classname).is_some());
+        let Some(classname) = classname else {
+            expanded.push_str(line);
+            continue;
+        };
+        let separator = if ending.is_empty() { "\n" } else { ending };
+        expanded.push_str(&format!(
+            "\"classname\" \"{}\"{separator}\"kind\" \"{classname}\"{ending}",
+            GenericProp::CLASS_INFO.name
+        ));
+    }
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"[
+        (classname: "crate_square", model: "models/crate_square.gltf", physics: Dynamic),
+    ]"#;
+
+    #[test]
+    fn expands_manifest_classes_into_generic_props() {
+        let manifest = PropManifest::from_ron(MANIFEST).unwrap();
+        let map = "{\n\"classname\" \"crate_square\"\n\"origin\" \"0 0 0\"\n}\n";
+        assert_eq!(
+            expand_prop_classes(map, &manifest),
+            "{\n\"classname\" \"generic_prop\"\n\"kind\" \"crate_square\"\n\"origin\" \"0 0 0\"\n}\n"
+        );
+    }
+
+    #[test]
+    fn keeps_other_classes_and_line_endings() {
+        let manifest = PropManifest::from_ron(MANIFEST).unwrap();
+        let map =
+            "{\r\n\"classname\" \"worldspawn\"\r\n}\r\n{\r\n\"classname\" \"crate_square\"\r\n}";
+        assert_eq!(
+            expand_prop_classes(map, &manifest),
+            "{\r\n\"classname\" \"worldspawn\"\r\n}\r\n{\r\n\"classname\" \"generic_prop\"\r\n\"kind\" \"crate_square\"\r\n}"
+        );
+    }
+
+    #[test]
+    fn rejects_the_generic_prop_classname() {
+        let manifest = r#"[(classname: "generic_prop", model: "a.gltf", physics: Static)]"#;
+        assert!(PropManifest::from_ron(manifest).is_err());
+    }
+}
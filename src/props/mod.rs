@@ -1,11 +1,13 @@
 //! Props are generic objects that can be placed in the level. This corresponds to what TrenchBroom calls an "Entity", not to be confused with Bevy's `Entity`.
 //! We use this file to define new props and register them with TrenchBroom so that they show up in the level editor.
+//! Props without any special logic don't need any Rust code: they are listed in `assets/generic.props.ron` instead.
 //! Afterwards, we still need to add new props to the `LevelAssets` struct to preload them for a given level.
 use bevy::prelude::*;
 
 mod brush_entity;
 mod effects;
-mod generic;
+pub(crate) mod manifest;
+pub(crate) mod nav_obstacle;
 mod setup;
 mod specific;
 
@@ -14,7 +16,8 @@ pub(super) fn plugin(app: &mut App) {
         setup::plugin,
         specific::plugin,
         effects::plugin,
-        generic::plugin,
+        manifest::plugin,
+        nav_obstacle::plugin,
         brush_entity::plugin,
     ));
 }
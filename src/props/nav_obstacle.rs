@@ -0,0 +1,369 @@
+//! Makes dynamic props visible to NPC navigation.
+//!
+//! Every dynamic prop with a big enough footprint is a [`NavObstacle`] and a landmass [`Character`], so that agents
+//! avoid it. Props that move, e.g. because they were thrown, report their velocity so that agents can avoid them
+//! ahead of time. Once props come to rest, they are carved into the navmesh: the polygons under their footprint
+//! get a type that costs more to walk on, so that agents path around crate piles instead of trying to squeeze
+//! through them. Only the polygons under the footprint change, and they are restored once the prop moves again.
+//! The carving happens on a [`CarvedNavmesh`] of its own, so the level's navmesh stays as it was baked.
+
+use avian3d::prelude::*;
+use bevy::{
+    ecs::entity::{EntityHashMap, EntityHashSet},
+    prelude::*,
+};
+use bevy_landmass::{Character, prelude::*};
+use bevy_rerecast::prelude::*;
+
+use crate::{PostPhysicsAppSystems, gameplay::level::LevelAssets, screens::Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            consider_nav_obstacles,
+            sync_nav_obstacle_velocity,
+            schedule_navmesh_carving,
+            copy_rebaked_level_navmesh,
+            carve_navmesh,
+        )
+            .chain()
+            .in_set(PostPhysicsAppSystems::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_observer(penalize_carved_polygons);
+    app.add_systems(OnExit(Screen::Gameplay), remove_carved_navmesh);
+}
+
+/// Props whose footprint has a smaller radius than this are small enough for agents to ignore.
+const MIN_OBSTACLE_RADIUS: f32 = 0.2;
+/// How long the resting props have to stay unchanged before the navmesh is carved,
+/// so that a pile of props coming to rest is only carved once.
+const CARVING_DELAY_SECS: f32 = 1.0;
+/// The area of carved polygons. `landmass_rerecast` uses the area of a polygon as its landmass type index.
+const CARVED_AREA: AreaType = AreaType(1);
+/// How much more walking across a carved polygon costs than walking across an uncarved one.
+/// Agents can still cross carved polygons when there is no way around.
+const CARVED_COST: f32 = 8.0;
+/// How far below the bottom of a prop a polygon still counts as the floor the prop rests on, in meters.
+const FLOOR_TOLERANCE: f32 = 0.5;
+
+/// A dynamic prop that agents avoid.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub(crate) struct NavObstacle;
+
+/// A rigid body that was already checked for whether it is a [`NavObstacle`].
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct NavObstacleConsidered;
+
+/// The copy of the level's navmesh that resting props are carved into. The level's island walks on this one.
+#[derive(Resource, Debug)]
+pub(crate) struct CarvedNavmesh {
+    pub(crate) handle: Handle<Navmesh>,
+    /// The polygons each resting obstacle carved, so that they can be restored once it moves again.
+    carved: EntityHashMap<Vec<usize>>,
+    /// Obstacles that started moving or were removed since the navmesh was last carved.
+    moved: EntityHashSet,
+    /// Whether the resting obstacles changed since the navmesh was last carved.
+    dirty: bool,
+    delay: Timer,
+}
+
+impl CarvedNavmesh {
+    /// Copies the level's navmesh into a new asset.
+    pub(crate) fn from_level(
+        level_assets: &LevelAssets,
+        navmeshes: &mut Assets<Navmesh>,
+    ) -> Result<Self> {
+        let navmesh = navmeshes
+            .get(&level_assets.navmesh)
+            .ok_or("The level's navmesh is not loaded")?
+            .clone();
+        Ok(Self {
+            handle: navmeshes.add(navmesh),
+            carved: default(),
+            moved: default(),
+            dirty: false,
+            delay: Timer::from_seconds(CARVING_DELAY_SECS, TimerMode::Once),
+        })
+    }
+}
+
+/// Turns new dynamic bodies with a big enough footprint into obstacles.
+/// Each body is only looked at once, unless its colliders change.
+fn consider_nav_obstacles(
+    mut commands: Commands,
+    bodies: Query<
+        (Entity, &RigidBody, &RigidBodyColliders),
+        Or<(Without<NavObstacleConsidered>, Changed<RigidBodyColliders>)>,
+    >,
+    colliders: Query<&ColliderAabb>,
+    archipelago: Single<Entity, With<Archipelago3d>>,
+) {
+    for (entity, rigid_body, body_colliders) in &bodies {
+        if !rigid_body.is_dynamic() {
+            commands.entity(entity).insert(NavObstacleConsidered);
+            continue;
+        }
+        let Some(aabb) = body_aabb(body_colliders, &colliders) else {
+            // The AABBs are only computed in the next physics step.
+            continue;
+        };
+        commands.entity(entity).insert(NavObstacleConsidered);
+
+        // The radius of the circle around the prop's footprint.
+        let footprint_radius = ((aabb.max - aabb.min) / 2.0).xz().length();
+        if footprint_radius < MIN_OBSTACLE_RADIUS {
+            commands
+                .entity(entity)
+                .remove::<(NavObstacle, Character3dBundle)>();
+            continue;
+        }
+        commands.entity(entity).insert((
+            NavObstacle,
+            Character3dBundle {
+                character: Character::default(),
+                settings: CharacterSettings {
+                    radius: footprint_radius,
+                },
+                archipelago_ref: ArchipelagoRef3d::new(*archipelago),
+            },
+        ));
+    }
+}
+
+/// The AABB around all colliders of a body, if they have been computed yet.
+fn body_aabb(
+    body_colliders: &RigidBodyColliders,
+    colliders: &Query<&ColliderAabb>,
+) -> Option<ColliderAabb> {
+    let aabb = colliders
+        .iter_many(body_colliders.iter())
+        .copied()
+        .reduce(|a, b| a.merged(b))?;
+    let size = aabb.max - aabb.min;
+    (size.is_finite() && !size.cmple(Vec3::ZERO).all()).then_some(aabb)
+}
+
+fn sync_nav_obstacle_velocity(
+    mut obstacles: Query<
+        (&LinearVelocity, &mut Velocity3d),
+        (With<NavObstacle>, Changed<LinearVelocity>),
+    >,
+) {
+    for (linear_velocity, mut velocity) in &mut obstacles {
+        velocity.velocity = linear_velocity.0;
+    }
+}
+
+fn schedule_navmesh_carving(
+    came_to_rest: Query<(), (With<NavObstacle>, Added<Sleeping>)>,
+    mut woke_up: RemovedComponents<Sleeping>,
+    mut removed: RemovedComponents<NavObstacle>,
+    obstacles: Query<(), With<NavObstacle>>,
+    mut carving: ResMut<CarvedNavmesh>,
+) {
+    let moved: Vec<_> = woke_up
+        .read()
+        .filter(|entity| obstacles.contains(*entity))
+        .chain(removed.read())
+        .collect();
+    if !came_to_rest.is_empty() || !moved.is_empty() {
+        carving.moved.extend(moved);
+        carving.dirty = true;
+        carving.delay.reset();
+    }
+}
+
+/// Starts over from the level's navmesh when it changed, e.g. because it was rebaked with `--rebake-navmesh`.
+fn copy_rebaked_level_navmesh(
+    mut events: MessageReader<AssetEvent<Navmesh>>,
+    level_assets: Res<LevelAssets>,
+    mut navmeshes: ResMut<Assets<Navmesh>>,
+    mut carving: ResMut<CarvedNavmesh>,
+) -> Result {
+    let level_navmesh_changed = events
+        .read()
+        .any(|event| event.is_modified(&level_assets.navmesh));
+    if !level_navmesh_changed {
+        return Ok(());
+    }
+    let navmesh = navmeshes
+        .get(&level_assets.navmesh)
+        .ok_or("The level's navmesh is not loaded")?
+        .clone();
+    navmeshes.insert(&carving.handle, navmesh)?;
+    // Every resting obstacle has to be carved into the new navmesh again.
+    carving.carved.clear();
+    carving.dirty = true;
+    Ok(())
+}
+
+fn carve_navmesh(
+    time: Res<Time>,
+    mut carving: ResMut<CarvedNavmesh>,
+    level_assets: Res<LevelAssets>,
+    mut navmeshes: ResMut<Assets<Navmesh>>,
+    resting: Query<(Entity, &RigidBodyColliders), (With<NavObstacle>, With<Sleeping>)>,
+    colliders: Query<&ColliderAabb>,
+) -> Result {
+    if !carving.dirty || !carving.delay.tick(time.delta()).is_finished() {
+        return Ok(());
+    }
+    carving.dirty = false;
+    let carving = &mut *carving;
+
+    let baked_areas = navmeshes
+        .get(&level_assets.navmesh)
+        .ok_or("The level's navmesh is not loaded")?
+        .polygon
+        .areas
+        .clone();
+    let navmesh = navmeshes
+        .get_mut(&carving.handle)
+        .ok_or("The carved navmesh is not available")?;
+    let polygons = &mut navmesh.polygon;
+
+    // Restore the footprints of obstacles that moved. They are carved again below if they are resting by now.
+    for entity in carving.moved.drain() {
+        for polygon in carving.carved.remove(&entity).unwrap_or_default() {
+            polygons.areas[polygon] = baked_areas[polygon];
+        }
+    }
+    for (entity, body_colliders) in &resting {
+        if carving.carved.contains_key(&entity) {
+            continue;
+        }
+        let Some(aabb) = body_aabb(body_colliders, &colliders) else {
+            continue;
+        };
+        let covered = covered_polygons(polygons, &aabb);
+        carving.carved.insert(entity, covered);
+    }
+    // A restored polygon can still be covered by another resting obstacle.
+    for polygon in carving.carved.values().flatten() {
+        polygons.areas[*polygon] = CARVED_AREA;
+    }
+    Ok(())
+}
+
+/// The walkable polygons of the navmesh under the footprint of `aabb`.
+fn covered_polygons(navmesh: &PolygonNavmesh, aabb: &ColliderAabb) -> Vec<usize> {
+    let origin = Vec3::from(navmesh.aabb.min);
+    let cell = Vec3::new(navmesh.cell_size, navmesh.cell_height, navmesh.cell_size);
+    let footprint = Rect::from_corners(aabb.min.xz(), aabb.max.xz());
+    // Each polygon takes up the same number of indices, of which the unused vertex slots are `u16::MAX`.
+    let stride = navmesh.polygons.len() / navmesh.areas.len().max(1);
+    let max_vertices = usize::from(navmesh.max_vertices_per_polygon).min(stride);
+
+    let mut covered = Vec::new();
+    let mut vertices = Vec::with_capacity(max_vertices);
+    for (polygon, area) in navmesh.areas.iter().enumerate() {
+        if *area == AreaType::NOT_WALKABLE {
+            continue;
+        }
+        vertices.clear();
+        vertices.extend(
+            navmesh.polygons[polygon * stride..][..max_vertices]
+                .iter()
+                .take_while(|index| **index != u16::MAX)
+                .map(|index| origin + navmesh.vertices[usize::from(*index)].as_vec3() * cell),
+        );
+        let (bottom, top) = vertices.iter().fold(
+            (f32::INFINITY, f32::NEG_INFINITY),
+            |(bottom, top), vertex| (bottom.min(vertex.y), top.max(vertex.y)),
+        );
+        if top < aabb.min.y - FLOOR_TOLERANCE || bottom > aabb.max.y {
+            continue;
+        }
+        let outline: Vec<Vec2> = vertices.iter().map(|vertex| vertex.xz()).collect();
+        if convex_polygon_overlaps_rect(&outline, footprint) {
+            covered.push(polygon);
+        }
+    }
+    covered
+}
+
+/// Whether a convex polygon and a rectangle overlap, by looking for an axis that separates them.
+fn convex_polygon_overlaps_rect(polygon: &[Vec2], rect: Rect) -> bool {
+    if polygon.is_empty() {
+        return false;
+    }
+    let corners = [
+        rect.min,
+        Vec2::new(rect.max.x, rect.min.y),
+        rect.max,
+        Vec2::new(rect.min.x, rect.max.y),
+    ];
+    let edge_normals = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| (*b - *a).perp());
+    [Vec2::X, Vec2::Y]
+        .into_iter()
+        .chain(edge_normals)
+        .filter(|axis| *axis != Vec2::ZERO)
+        .all(|axis| {
+            let project = |points: &[Vec2]| {
+                points
+                    .iter()
+                    .map(|point| point.dot(axis))
+                    .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+                        (min.min(value), max.max(value))
+                    })
+            };
+            let (polygon_min, polygon_max) = project(polygon);
+            let (rect_min, rect_max) = project(&corners);
+            polygon_min <= rect_max && rect_min <= polygon_max
+        })
+}
+
+fn penalize_carved_polygons(
+    add: On<Add, Archipelago3d>,
+    mut archipelagos: Query<&mut Archipelago3d>,
+) -> Result {
+    let mut archipelago = archipelagos.get_mut(add.entity)?;
+    archipelago
+        .set_type_index_cost(usize::from(CARVED_AREA.0), CARVED_COST)
+        .map_err(|error| format!("Failed to set the cost of carved polygons: {error:?}"))?;
+    Ok(())
+}
+
+fn remove_carved_navmesh(mut commands: Commands) {
+    commands.remove_resource::<CarvedNavmesh>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn footprint_overlaps_only_touching_polygons() {
+        let footprint = Rect::new(0.0, 0.0, 1.0, 1.0);
+        let triangle = |offset: Vec2| {
+            [Vec2::ZERO, Vec2::new(2.0, 0.0), Vec2::new(0.0, 2.0)].map(|vertex| vertex + offset)
+        };
+        // Covers part of the footprint.
+        assert!(convex_polygon_overlaps_rect(
+            &triangle(Vec2::splat(-0.5)),
+            footprint
+        ));
+        // Inside of the footprint.
+        assert!(convex_polygon_overlaps_rect(
+            &triangle(Vec2::splat(0.2)).map(|vertex| vertex * 0.2),
+            footprint
+        ));
+        // Only the bounding boxes overlap, the diagonal edge separates them.
+        assert!(!convex_polygon_overlaps_rect(
+            &triangle(Vec2::splat(-1.1)),
+            footprint
+        ));
+        // Far away.
+        assert!(!convex_polygon_overlaps_rect(
+            &triangle(Vec2::new(5.0, 0.0)),
+            footprint
+        ));
+    }
+}
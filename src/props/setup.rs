@@ -20,48 +20,29 @@ pub(crate) fn setup_static_prop_with_convex_hull<T: QuakeClass>(
     commands.entity(add.entity).insert(bundle);
 }
 
-pub(crate) fn setup_nonphysical_prop<T: QuakeClass>(
-    add: On<Add, T>,
-    asset_server: Res<AssetServer>,
-    mut commands: Commands,
-) {
-    let model = asset_server.load_trenchbroom_model::<T>();
-    commands.entity(add.entity).insert(SceneRoot(model));
-}
-
-pub(crate) fn setup_static_prop_with_convex_decomposition<T: QuakeClass>(
-    add: On<Add, T>,
-    asset_server: Res<AssetServer>,
-    mut commands: Commands,
-) {
-    let bundle = static_bundle::<T>(
-        &asset_server,
-        ColliderConstructor::ConvexDecompositionFromMesh,
-    );
-    commands.entity(add.entity).insert(bundle);
-}
-
-pub(crate) fn setup_dynamic_prop_with_convex_hull<T: QuakeClass>(
-    add: On<Add, T>,
-    asset_server: Res<AssetServer>,
-    mut commands: Commands,
-) {
-    let bundle = dynamic_bundle::<T>(&asset_server, ColliderConstructor::ConvexHullFromMesh);
-    commands.entity(add.entity).insert(bundle);
-}
-
 pub(crate) fn dynamic_bundle<T: QuakeClass>(
     asset_server: &AssetServer,
     constructor: ColliderConstructor,
 ) -> impl Bundle {
-    let model = asset_server.load_trenchbroom_model::<T>();
+    // About the density of oak wood (600-800 kg/m^3)
+    dynamic_scene_bundle(
+        asset_server.load_trenchbroom_model::<T>(),
+        constructor,
+        800.0,
+    )
+}
+
+pub(crate) fn dynamic_scene_bundle(
+    scene: Handle<Scene>,
+    constructor: ColliderConstructor,
+    density: f32,
+) -> impl Bundle {
     (
         ColliderConstructorHierarchy::new(constructor)
             .with_default_layers(CollisionLayers::new(CollisionLayer::Prop, LayerMask::ALL))
-            // About the density of oak wood (600-800 kg/m^3)
-            .with_default_density(800.0),
+            .with_default_density(density),
         RigidBody::Dynamic,
-        SceneRoot(model),
+        SceneRoot(scene),
     )
 }
 
@@ -69,13 +50,19 @@ pub(crate) fn static_bundle<T: QuakeClass>(
     asset_server: &AssetServer,
     constructor: ColliderConstructor,
 ) -> impl Bundle {
-    let model = asset_server.load_trenchbroom_model::<T>();
+    static_scene_bundle(asset_server.load_trenchbroom_model::<T>(), constructor)
+}
+
+pub(crate) fn static_scene_bundle(
+    scene: Handle<Scene>,
+    constructor: ColliderConstructor,
+) -> impl Bundle {
     (
         ColliderConstructorHierarchy::new(constructor).with_default_layers(CollisionLayers::new(
             CollisionLayer::Default,
             LayerMask::ALL,
         )),
         RigidBody::Static,
-        SceneRoot(model),
+        SceneRoot(scene),
     )
 }
@@ -13,7 +13,8 @@ use crate::asset_tracking::LoadResource as _;
 use crate::third_party::bevy_trenchbroom::GetTrenchbroomModelPath as _;
 use crate::{
     PostPhysicsAppSystems,
-    audio::SpatialPool,
+    audio::{SpatialPool, caption::Caption},
+    graphics::GraphicsSettings,
     props::{effects::disable_shadow_casting_on_instance_ready, setup::static_bundle},
     screens::Screen,
 };
@@ -30,7 +31,14 @@ pub(super) fn plugin(app: &mut App) {
     );
     app.add_observer(setup_burning_logs);
     app.load_asset::<Gltf>(BurningLogs::model_path());
+    app.init_resource::<FireEffect>();
     app.add_observer(add_particle_effects);
+    app.add_systems(
+        Update,
+        update_particle_density
+            .run_if(resource_changed::<GraphicsSettings>)
+            .in_set(PostPhysicsAppSystems::Update),
+    );
 }
 
 #[point_class(
@@ -70,6 +78,8 @@ pub(crate) const TEXTURE_PATH: &str = {
 const SOUND_PATH: &str = "audio/music/loop_flames_03.ogg";
 
 const BASE_INTENSITY: f32 = 150_000.0;
+/// Particles per second at 100% particle density.
+const BASE_SPAWN_RATE: f32 = 150.0;
 
 fn setup_burning_logs(
     add: On<Add, BurningLogs>,
@@ -88,6 +98,8 @@ fn setup_burning_logs(
                 .looping()
                 .with_volume(Volume::Linear(0.25)),
             SpatialPool,
+            // The fire is quiet, so it is only captioned close by.
+            Caption::new("[fire crackling]").with_range(8.0),
         ))
         .observe(disable_shadow_casting_on_instance_ready)
         .with_child((
@@ -116,21 +128,68 @@ fn flicker_light(time: Res<Time>, mut query: Query<&mut PointLight, With<Flicker
     }
 }
 
+/// The fire effect shared by all burning logs.
+#[derive(Resource, Debug, Default)]
+struct FireEffect {
+    effect: Option<Handle<EffectAsset>>,
+    /// The particle density the effect was built for.
+    particle_density: u32,
+}
+
+impl FireEffect {
+    /// The effect for the given particle density, which is only built again when the density changed.
+    fn get_or_build(
+        &mut self,
+        effects: &mut Assets<EffectAsset>,
+        particle_density: u32,
+    ) -> Handle<EffectAsset> {
+        if let Some(effect) = &self.effect {
+            if self.particle_density == particle_density {
+                return effect.clone();
+            }
+        }
+        let spawn_rate = BASE_SPAWN_RATE * particle_density as f32 / 100.0;
+        let effect = setup_particles(effects, spawn_rate);
+        self.effect = Some(effect.clone());
+        self.particle_density = particle_density;
+        effect
+    }
+}
+
 pub(super) fn add_particle_effects(
     add: On<Add, BurningLogs>,
     asset_server: Res<AssetServer>,
     mut effects: ResMut<Assets<EffectAsset>>,
+    mut fire_effect: ResMut<FireEffect>,
+    settings: Res<GraphicsSettings>,
     mut commands: Commands,
 ) {
-    let particle_bundle = particle_bundle(&asset_server, &mut effects);
-    commands.entity(add.entity).insert(particle_bundle);
+    let effect = fire_effect.get_or_build(&mut effects, settings.particle_density);
+    commands
+        .entity(add.entity)
+        .insert(particle_bundle(&asset_server, effect));
 }
 
-fn particle_bundle(asset_server: &AssetServer, effects: &mut Assets<EffectAsset>) -> impl Bundle {
-    let effect_handle = setup_particles(effects);
+/// Switches the burning logs over to an effect that matches the new particle density.
+fn update_particle_density(
+    mut burning_logs: Query<&mut ParticleEffect, With<BurningLogs>>,
+    mut effects: ResMut<Assets<EffectAsset>>,
+    mut fire_effect: ResMut<FireEffect>,
+    settings: Res<GraphicsSettings>,
+) {
+    if fire_effect.effect.is_none() || fire_effect.particle_density == settings.particle_density {
+        return;
+    }
+    let effect = fire_effect.get_or_build(&mut effects, settings.particle_density);
+    for mut particle_effect in &mut burning_logs {
+        particle_effect.handle = effect.clone();
+    }
+}
+
+fn particle_bundle(asset_server: &AssetServer, effect: Handle<EffectAsset>) -> impl Bundle {
     let texture: Handle<Image> = asset_server.load(TEXTURE_PATH);
     (
-        ParticleEffect::new(effect_handle),
+        ParticleEffect::new(effect),
         RenderLayers::from(RenderLayer::PARTICLES),
         EffectMaterial {
             images: vec![texture.clone()],
@@ -138,7 +197,7 @@ fn particle_bundle(asset_server: &AssetServer, effects: &mut Assets<EffectAsset>
     )
 }
 
-fn setup_particles(effects: &mut Assets<EffectAsset>) -> Handle<EffectAsset> {
+fn setup_particles(effects: &mut Assets<EffectAsset>, spawn_rate: f32) -> Handle<EffectAsset> {
     let writer = ExprWriter::new();
 
     // Random upward velocity with some lateral randomness for flicker
@@ -205,17 +264,21 @@ fn setup_particles(effects: &mut Assets<EffectAsset>) -> Handle<EffectAsset> {
     };
 
     const MAX_PARTICLES: u32 = 32768;
-    let effect = EffectAsset::new(MAX_PARTICLES, SpawnerSettings::rate(150.0.into()), module)
-        .with_name("FireEffect")
-        .init(init_pos)
-        .init(velocity)
-        .init(lifetime)
-        .with_alpha_mode(alpha_mode)
-        .update(update_accel)
-        .render(orientation)
-        .render(color_over_lifetime)
-        .render(particle_texture_modifier)
-        .render(size_over_lifetime);
+    let effect = EffectAsset::new(
+        MAX_PARTICLES,
+        SpawnerSettings::rate(spawn_rate.into()),
+        module,
+    )
+    .with_name("FireEffect")
+    .init(init_pos)
+    .init(velocity)
+    .init(lifetime)
+    .with_alpha_mode(alpha_mode)
+    .update(update_accel)
+    .render(orientation)
+    .render(color_over_lifetime)
+    .render(particle_texture_modifier)
+    .render(size_over_lifetime);
 
     effects.add(effect)
 }
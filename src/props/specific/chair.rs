@@ -1,6 +1,5 @@
 use avian3d::prelude::*;
 use bevy::prelude::*;
-use bevy_landmass::{Character, prelude::*};
 
 use bevy_trenchbroom::prelude::*;
 
@@ -23,19 +22,8 @@ pub(super) fn plugin(app: &mut App) {
 )]
 pub(crate) struct Chair;
 
-fn setup_chair(
-    add: On<Add, Chair>,
-    asset_server: Res<AssetServer>,
-    mut commands: Commands,
-    archipelago: Single<Entity, With<Archipelago3d>>,
-) {
+fn setup_chair(add: On<Add, Chair>, asset_server: Res<AssetServer>, mut commands: Commands) {
     let model = asset_server.load_trenchbroom_model::<Chair>();
-    commands.entity(add.entity).insert(Character3dBundle {
-        character: Character::default(),
-        settings: CharacterSettings { radius: 0.4 },
-        archipelago_ref: ArchipelagoRef3d::new(*archipelago),
-    });
-
     commands.entity(add.entity).insert((
         // The chair has a fairly complex shape, so let's use a convex decomposition.
         ColliderConstructorHierarchy::new(ColliderConstructor::ConvexDecompositionFromMesh)
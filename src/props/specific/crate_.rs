@@ -1,7 +1,6 @@
 use avian_pickup::prop::PreferredPickupRotation;
 use avian3d::prelude::*;
 use bevy::prelude::*;
-use bevy_landmass::{Character, prelude::*};
 
 use bevy_trenchbroom::prelude::*;
 
@@ -37,14 +36,8 @@ fn setup_crate_small(
     add: On<Add, CrateSmall>,
     asset_server: Res<AssetServer>,
     mut commands: Commands,
-    archipelago: Single<Entity, With<Archipelago3d>>,
 ) {
     let model = asset_server.load_trenchbroom_model::<CrateSmall>();
-    commands.entity(add.entity).insert(Character3dBundle {
-        character: Character::default(),
-        settings: CharacterSettings { radius: 0.5 },
-        archipelago_ref: ArchipelagoRef3d::new(*archipelago),
-    });
     commands.entity(add.entity).insert((
         ColliderConstructorHierarchy::new(ColliderConstructor::ConvexHullFromMesh)
             .with_default_layers(CollisionLayers::new(CollisionLayer::Prop, LayerMask::ALL))
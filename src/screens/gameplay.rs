@@ -3,7 +3,10 @@
 use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
 use bevy_fix_cursor_unlock_web::ForceUnlockCursor;
 
-use crate::{Pause, menus::Menu, screens::Screen};
+use crate::{
+    Pause, gameplay::readable::ui::is_reader_open, menus::Menu, screens::Screen,
+    theme::navigation::gamepad_just_pressed,
+};
 
 pub(super) fn plugin(app: &mut App) {
     // Toggle pause on key press.
@@ -13,12 +16,20 @@ pub(super) fn plugin(app: &mut App) {
             (pause, spawn_pause_overlay, open_pause_menu).run_if(
                 in_state(Screen::Gameplay)
                     .and(in_state(Menu::None))
-                    .and(input_just_pressed(KeyCode::KeyP).or(input_just_pressed(KeyCode::Escape))),
+                    .and(not(is_reader_open))
+                    .and(
+                        input_just_pressed(KeyCode::KeyP)
+                            .or(input_just_pressed(KeyCode::Escape))
+                            .or(gamepad_just_pressed(GamepadButton::Start)),
+                    ),
             ),
             close_menu.run_if(
                 in_state(Screen::Gameplay)
                     .and(not(in_state(Menu::None)))
-                    .and(input_just_pressed(KeyCode::KeyP)),
+                    .and(
+                        input_just_pressed(KeyCode::KeyP)
+                            .or(gamepad_just_pressed(GamepadButton::Start)),
+                    ),
             ),
         ),
     );
@@ -34,11 +45,11 @@ fn unpause(mut next_pause: ResMut<NextState<Pause>>) {
     next_pause.set(Pause(false));
 }
 
-fn pause(mut next_pause: ResMut<NextState<Pause>>) {
+pub(crate) fn pause(mut next_pause: ResMut<NextState<Pause>>) {
     next_pause.set(Pause(true));
 }
 
-fn spawn_pause_overlay(mut commands: Commands) {
+pub(crate) fn spawn_pause_overlay(mut commands: Commands) {
     commands.spawn((
         Name::new("Pause Overlay"),
         Node {
@@ -52,7 +63,7 @@ fn spawn_pause_overlay(mut commands: Commands) {
     ));
 }
 
-fn open_pause_menu(mut next_menu: ResMut<NextState<Menu>>) {
+pub(crate) fn open_pause_menu(mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Pause);
 }
 
@@ -4,7 +4,10 @@
 use bevy::prelude::*;
 
 use crate::{
-    shader_compilation::{LoadedPipelineCount, all_pipelines_loaded, spawn_shader_compilation_map},
+    shader_compilation::{
+        PipelineManifest, ShaderCompilation, all_pipelines_loaded,
+        restart_shader_compilation_settling, spawn_shader_compilation_map,
+    },
     theme::{palette::SCREEN_BACKGROUND, prelude::*},
 };
 
@@ -14,7 +17,11 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         OnEnter(LoadingScreen::Shaders),
         (
-            spawn_or_skip_shader_compilation_loading_screen,
+            (
+                restart_shader_compilation_settling,
+                spawn_or_skip_shader_compilation_loading_screen,
+            )
+                .chain(),
             spawn_shader_compilation_map,
         ),
     );
@@ -32,10 +39,11 @@ pub(super) fn plugin(app: &mut App) {
 
 fn spawn_or_skip_shader_compilation_loading_screen(
     mut commands: Commands,
-    loaded_pipeline_count: Res<LoadedPipelineCount>,
+    compilation: Res<ShaderCompilation>,
+    manifest: Res<PipelineManifest>,
     mut next_screen: ResMut<NextState<LoadingScreen>>,
 ) {
-    if loaded_pipeline_count.is_done() {
+    if compilation.is_done(&manifest) {
         next_screen.set(LoadingScreen::Level);
         return;
     }
@@ -57,13 +65,14 @@ struct LoadingShadersLabel;
 
 fn update_loading_shaders_label(
     mut query: Query<&mut Text, With<LoadingShadersLabel>>,
-    loaded_pipeline_count: Res<LoadedPipelineCount>,
+    compilation: Res<ShaderCompilation>,
+    manifest: Res<PipelineManifest>,
 ) {
     for mut text in query.iter_mut() {
         text.0 = format!(
             "Compiling shaders: {} / {}",
-            loaded_pipeline_count.0,
-            LoadedPipelineCount::TOTAL_PIPELINES
+            compilation.ready_count(),
+            compilation.expected_count(&manifest)
         );
     }
 }
@@ -1,6 +1,6 @@
 //! The game's main screen states and transitions between them.
 
-mod gameplay;
+pub(crate) mod gameplay;
 pub(crate) mod loading;
 mod splash;
 mod title;
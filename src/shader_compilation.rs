@@ -1,18 +1,38 @@
-use bevy::prelude::*;
+//! Compiles the shaders of all effects before the level is spawned by spawning the `compile_shaders` map
+//! during the loading screen.
+//!
+//! Loading is done once every pipeline recorded in the pipeline manifest of the current feature combination is ready.
+//! Manifests are recorded by running a native build with `--record-pipeline-manifest`, which writes all pipelines
+//! that were compiled for the `compile_shaders` map. Web builds can't write files, so when their manifest is stale
+//! they log the manifest to record instead. If the manifest is empty or stale, e.g. because a new effect
+//! was added without recording it again, loading is done once no new pipelines showed up for a while instead.
+
+use std::hash::{Hash as _, Hasher};
 
-use bevy::render::render_resource::{CachedPipelineState, PipelineCache};
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use bevy::render::render_resource::{CachedPipelineState, PipelineCache, PipelineDescriptor};
 use bevy::render::{MainWorld, RenderApp};
 
 use crate::asset_tracking::LoadResource as _;
 use crate::screens::loading::LoadingScreen;
+use crate::stable_hash::Fnv1aHasher;
 
 pub(super) fn plugin(app: &mut App) {
     app.load_resource::<CompileShadersAssets>();
 
-    app.init_resource::<LoadedPipelineCount>();
+    app.init_resource::<ShaderCompilation>();
+    app.insert_resource(PipelineManifest::parse(PIPELINE_MANIFEST));
 
     app.sub_app_mut(RenderApp)
-        .add_systems(ExtractSchedule, update_loaded_pipeline_count);
+        .add_systems(ExtractSchedule, update_shader_compilation);
+
+    app.add_systems(OnExit(LoadingScreen::Shaders), warn_if_manifest_is_stale);
+
+    #[cfg(feature = "native")]
+    if std::env::args().any(|arg| arg == "--record-pipeline-manifest") {
+        app.add_systems(OnExit(LoadingScreen::Shaders), record_pipeline_manifest);
+    }
 }
 
 pub(crate) fn spawn_shader_compilation_map(
@@ -45,55 +65,338 @@ impl FromWorld for CompileShadersAssets {
     }
 }
 
-/// A `Resource` in the main world that stores the number of pipelines that are ready.
-#[derive(Resource, Default, Debug, Deref, DerefMut, Reflect)]
-#[reflect(Resource)]
-pub(crate) struct LoadedPipelineCount(pub(crate) usize);
+/// The directory containing one pipeline manifest per feature combination.
+#[cfg(feature = "native")]
+const PIPELINE_MANIFEST_DIR: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/assets/maps/compile_shaders/pipelines"
+);
 
-impl LoadedPipelineCount {
-    pub(crate) fn is_done(&self) -> bool {
-        self.0 >= Self::TOTAL_PIPELINES
+/// The file name of the pipeline manifest for the current feature combination.
+const PIPELINE_MANIFEST_NAME: &str = {
+    #[cfg(all(feature = "native", feature = "dev"))]
+    {
+        "native_dev.txt"
+    }
+    #[cfg(all(feature = "native", feature = "release"))]
+    {
+        "native_release.txt"
+    }
+    #[cfg(all(feature = "web", feature = "dev"))]
+    {
+        "web_dev.txt"
+    }
+    #[cfg(all(feature = "web", feature = "release"))]
+    {
+        "web_release.txt"
     }
+};
 
-    /// These numbers have to be tuned by hand, unfortunately.
-    /// Find them out with `bevy run` and `bevy run web`.
-    pub(crate) const TOTAL_PIPELINES: usize = {
-        let count = {
-            #[cfg(feature = "native")]
-            {
-                74
-            }
-            #[cfg(feature = "web")]
-            {
-                55
+/// The pipeline manifest is embedded so that it is available before any assets are loaded.
+const PIPELINE_MANIFEST: &str = {
+    #[cfg(all(feature = "native", feature = "dev"))]
+    {
+        include_str!("../assets/maps/compile_shaders/pipelines/native_dev.txt")
+    }
+    #[cfg(all(feature = "native", feature = "release"))]
+    {
+        include_str!("../assets/maps/compile_shaders/pipelines/native_release.txt")
+    }
+    #[cfg(all(feature = "web", feature = "dev"))]
+    {
+        include_str!("../assets/maps/compile_shaders/pipelines/web_dev.txt")
+    }
+    #[cfg(all(feature = "web", feature = "release"))]
+    {
+        include_str!("../assets/maps/compile_shaders/pipelines/web_release.txt")
+    }
+};
+
+/// How many frames without any new pipelines it takes to consider shader compilation done
+/// when the pipeline manifest is stale.
+const SETTLE_FRAMES: u32 = 60;
+
+/// The keys of all pipelines that the `compile_shaders` map is expected to compile.
+/// See [`pipeline_key`] for what identifies a pipeline.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct PipelineManifest(HashSet<u64>);
+
+impl PipelineManifest {
+    /// Parses lines of `<key in hex> <label>`. Empty lines and lines starting with `#` are ignored.
+    fn parse(manifest: &str) -> Self {
+        let keys = manifest
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let key = line.split_whitespace().next()?;
+                let key = u64::from_str_radix(key, 16);
+                if key.is_err() {
+                    warn!("Ignoring invalid line in pipeline manifest {PIPELINE_MANIFEST_NAME}: {line}");
+                }
+                key.ok()
+            })
+            .collect();
+        Self(keys)
+    }
+}
+
+/// A `Resource` in the main world that tracks which pipelines are ready.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct ShaderCompilation {
+    /// The keys and labels of all pipelines that finished compiling.
+    ready: HashMap<u64, String>,
+    /// The number of pipelines that finished compiling, but have no key because they can't be recorded.
+    ready_without_key: usize,
+    /// The number of pipelines that are still being compiled.
+    pending: usize,
+    /// The number of pipelines the pipeline cache knows about, including pending ones.
+    total: usize,
+    /// How many frames passed since the last time a pipeline was added or finished compiling.
+    frames_without_new_pipelines: u32,
+}
+
+impl ShaderCompilation {
+    pub(crate) fn ready_count(&self) -> usize {
+        self.ready.len() + self.ready_without_key
+    }
+
+    /// The number of pipelines we are waiting for, as far as we know.
+    pub(crate) fn expected_count(&self, manifest: &PipelineManifest) -> usize {
+        manifest.0.len().max(self.total)
+    }
+
+    pub(crate) fn is_done(&self, manifest: &PipelineManifest) -> bool {
+        let all_recorded_pipelines_ready =
+            !manifest.0.is_empty() && !self.has_outstanding_pipelines(manifest);
+        all_recorded_pipelines_ready || self.is_settled()
+    }
+
+    /// Whether any pipeline in the manifest is not ready yet.
+    fn has_outstanding_pipelines(&self, manifest: &PipelineManifest) -> bool {
+        manifest.0.iter().any(|key| !self.ready.contains_key(key))
+    }
+
+    fn is_settled(&self) -> bool {
+        self.pending == 0 && self.frames_without_new_pipelines >= SETTLE_FRAMES
+    }
+
+    /// The contents of a pipeline manifest with all pipelines that are ready, sorted by label.
+    fn manifest(&self) -> String {
+        let mut pipelines: Vec<_> = self.ready.iter().collect();
+        pipelines.sort_by(|(a_key, a_label), (b_key, b_label)| {
+            a_label.cmp(b_label).then(a_key.cmp(b_key))
+        });
+        let mut manifest = String::from(
+            "# Pipelines compiled by the `compile_shaders` map. Record again with `--record-pipeline-manifest`.\n",
+        );
+        for (key, label) in pipelines {
+            manifest.push_str(&format!("{key:016x} {label}\n"));
+        }
+        manifest
+    }
+
+    /// The number of pipelines in the manifest that are not ready, and the number of ready pipelines not in the manifest.
+    fn manifest_mismatch(&self, manifest: &PipelineManifest) -> (usize, usize) {
+        let missing = manifest
+            .0
+            .iter()
+            .filter(|key| !self.ready.contains_key(*key))
+            .count();
+        let unrecorded = self
+            .ready
+            .keys()
+            .filter(|key| !manifest.0.contains(*key))
+            .count();
+        (missing, unrecorded)
+    }
+}
+
+/// Pipelines compiled before entering the loading screen, e.g. for the menus, should not count as settled
+/// while recorded pipelines are still outstanding. Once they are all ready, e.g. when loading the level again,
+/// there is nothing to wait for.
+pub(crate) fn restart_shader_compilation_settling(
+    mut compilation: ResMut<ShaderCompilation>,
+    manifest: Res<PipelineManifest>,
+) {
+    if compilation.has_outstanding_pipelines(&manifest) {
+        compilation.frames_without_new_pipelines = 0;
+    }
+}
+
+/// Identifies a pipeline across runs by everything that goes into compiling it.
+/// Shaders are identified by their path, or by their UUID if they were added in code with a fixed one.
+/// Pipelines with a shader that gets a new ID in every run have no key and are not recorded.
+///
+/// The key hashes `usize`s, so it differs between native and web. Each of them records its own manifest anyway.
+fn pipeline_key(descriptor: &PipelineDescriptor) -> Option<(u64, String)> {
+    fn hash_shader(shader: &Handle<Shader>, hasher: &mut impl Hasher) -> Option<()> {
+        match (shader.path(), shader.id()) {
+            (Some(path), _) => path.to_string().hash(hasher),
+            (None, AssetId::Uuid { uuid }) => uuid.as_u128().hash(hasher),
+            (None, AssetId::Index { .. }) => return None,
+        }
+        Some(())
+    }
+
+    let mut hasher = Fnv1aHasher::default();
+    let label = match descriptor {
+        PipelineDescriptor::RenderPipelineDescriptor(descriptor) => {
+            hash_shader(&descriptor.vertex.shader, &mut hasher)?;
+            descriptor.vertex.shader_defs.hash(&mut hasher);
+            descriptor.vertex.entry_point.hash(&mut hasher);
+            descriptor.vertex.buffers.hash(&mut hasher);
+            if let Some(fragment) = &descriptor.fragment {
+                hash_shader(&fragment.shader, &mut hasher)?;
+                fragment.shader_defs.hash(&mut hasher);
+                fragment.entry_point.hash(&mut hasher);
+                fragment.targets.hash(&mut hasher);
             }
-        };
-        #[cfg(feature = "dev")]
-        {
-            count
+            descriptor.primitive.hash(&mut hasher);
+            descriptor.depth_stencil.hash(&mut hasher);
+            descriptor.multisample.hash(&mut hasher);
+            descriptor.label.clone()
         }
-        #[cfg(feature = "release")]
-        {
-            count - 1
+        PipelineDescriptor::ComputePipelineDescriptor(descriptor) => {
+            hash_shader(&descriptor.shader, &mut hasher)?;
+            descriptor.shader_defs.hash(&mut hasher);
+            descriptor.entry_point.hash(&mut hasher);
+            descriptor.label.clone()
         }
     };
+    let label = label.unwrap_or_else(|| "unlabeled".into()).into_owned();
+    label.hash(&mut hasher);
+    Some((hasher.finish(), label))
 }
 
-fn update_loaded_pipeline_count(mut main_world: ResMut<MainWorld>, cache: Res<PipelineCache>) {
-    if let Some(mut pipelines_ready) = main_world.get_resource_mut::<LoadedPipelineCount>() {
-        let count = cache
-            .pipelines()
-            .filter(|pipeline| matches!(pipeline.state, CachedPipelineState::Ok(_)))
-            .count();
-
-        if pipelines_ready.0 == count {
-            return;
+fn update_shader_compilation(
+    mut main_world: ResMut<MainWorld>,
+    cache: Res<PipelineCache>,
+    // Pipeline IDs are indices into the cache, so we only need to compute the key of each pipeline once.
+    mut keys: Local<HashMap<usize, Option<(u64, String)>>>,
+) {
+    let Some(mut compilation) = main_world.get_resource_mut::<ShaderCompilation>() else {
+        return;
+    };
+    let mut pending = 0;
+    let mut total = 0;
+    let mut new_ready = Vec::new();
+    let mut new_ready_without_key = 0;
+    for (id, pipeline) in cache.pipelines().enumerate() {
+        total += 1;
+        match pipeline.state {
+            CachedPipelineState::Ok(_) => {
+                if !keys.contains_key(&id) {
+                    let key = pipeline_key(&pipeline.descriptor);
+                    match &key {
+                        Some(key) => new_ready.push(key.clone()),
+                        None => new_ready_without_key += 1,
+                    }
+                    keys.insert(id, key);
+                }
+            }
+            CachedPipelineState::Queued | CachedPipelineState::Creating(_) => pending += 1,
+            CachedPipelineState::Err(_) => {}
         }
+    }
+
+    if new_ready.is_empty()
+        && new_ready_without_key == 0
+        && total == compilation.total
+        && pending == compilation.pending
+    {
+        // Avoid triggering change detection every frame.
+        compilation
+            .bypass_change_detection()
+            .frames_without_new_pipelines += 1;
+        return;
+    }
+    compilation.ready.extend(new_ready);
+    compilation.ready_without_key += new_ready_without_key;
+    compilation.pending = pending;
+    compilation.total = total;
+    compilation.frames_without_new_pipelines = 0;
+}
 
-        pipelines_ready.0 = count;
+pub(crate) fn all_pipelines_loaded(
+    compilation: Res<ShaderCompilation>,
+    manifest: Res<PipelineManifest>,
+) -> bool {
+    compilation.is_done(&manifest)
+}
+
+fn warn_if_manifest_is_stale(
+    compilation: Res<ShaderCompilation>,
+    manifest: Res<PipelineManifest>,
+    mut warned: Local<bool>,
+) {
+    if *warned {
+        return;
+    }
+    let (missing, unrecorded) = compilation.manifest_mismatch(&manifest);
+    if missing == 0 && unrecorded == 0 {
+        return;
     }
+    *warned = true;
+    warn!(
+        "The pipeline manifest {PIPELINE_MANIFEST_NAME} is stale: {missing} recorded pipelines were not compiled \
+        and {unrecorded} compiled pipelines were not recorded. Record it again by running the game with `--record-pipeline-manifest`."
+    );
+    #[cfg(feature = "web")]
+    warn!(
+        "Web builds can't record the manifest themselves. Replace the contents of \
+        assets/maps/compile_shaders/pipelines/{PIPELINE_MANIFEST_NAME} with:\n{}",
+        compilation.manifest()
+    );
 }
 
-pub(crate) fn all_pipelines_loaded(loaded_pipeline_count: Res<LoadedPipelineCount>) -> bool {
-    loaded_pipeline_count.is_done()
+/// Writes all pipelines that are ready into the pipeline manifest of the current feature combination.
+#[cfg(feature = "native")]
+fn record_pipeline_manifest(compilation: Res<ShaderCompilation>) {
+    let path = format!("{PIPELINE_MANIFEST_DIR}/{PIPELINE_MANIFEST_NAME}");
+    match std::fs::write(&path, compilation.manifest()) {
+        Ok(()) => info!("Recorded {} pipelines into {path}", compilation.ready.len()),
+        Err(error) => error!("Failed to write the pipeline manifest to {path}: {error}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_manifest_parses_back() {
+        let mut compilation = ShaderCompilation::default();
+        compilation
+            .ready
+            .insert(0xff, "pbr_opaque_mesh_pipeline".into());
+        compilation
+            .ready
+            .insert(0x1234_5678_9abc_def0, "bloom_downsampling_pipeline".into());
+        let manifest = compilation.manifest();
+        assert!(manifest.starts_with('#'));
+        assert_eq!(
+            manifest.lines().nth(1),
+            Some("123456789abcdef0 bloom_downsampling_pipeline")
+        );
+        assert_eq!(
+            PipelineManifest::parse(&manifest).0,
+            [0xff, 0x1234_5678_9abc_def0]
+                .into_iter()
+                .collect::<HashSet<_>>()
+        );
+    }
+
+    #[test]
+    fn only_unready_recorded_pipelines_are_outstanding() {
+        let manifest = PipelineManifest::parse("00ff pbr_opaque_mesh_pipeline\n");
+        let mut compilation = ShaderCompilation::default();
+        assert!(!compilation.has_outstanding_pipelines(&PipelineManifest::default()));
+        assert!(compilation.has_outstanding_pipelines(&manifest));
+
+        compilation
+            .ready
+            .insert(0xff, "pbr_opaque_mesh_pipeline".into());
+        assert!(!compilation.has_outstanding_pipelines(&manifest));
+    }
 }
@@ -3,6 +3,8 @@ use bevy_seedling::sample::{AudioSample, SamplePlayer};
 
 use crate::{PostPhysicsAppSystems, asset_tracking::LoadResource, audio::SfxPool};
 
+use super::navigation::FocusedWidget;
+
 pub(super) fn plugin(app: &mut App) {
     app.load_resource::<InteractionAssets>();
     app.add_systems(
@@ -26,6 +28,8 @@ pub(crate) struct InteractionPalette {
     pub(crate) none: Color,
     pub(crate) hovered: Color,
     pub(crate) pressed: Color,
+    /// Used while the widget is the [`FocusedWidget`] and not hovered or pressed.
+    pub(crate) focused: Color,
 }
 
 /// Event triggered on a UI entity when the [`Interaction`] component on the same entity changes to
@@ -47,13 +51,20 @@ fn trigger_on_press(
 }
 
 fn apply_interaction_palette(
-    mut palette_query: Query<
-        (&Interaction, &InteractionPalette, &mut BackgroundColor),
-        Changed<Interaction>,
-    >,
+    mut palette_query: Query<(
+        Entity,
+        Ref<Interaction>,
+        &InteractionPalette,
+        &mut BackgroundColor,
+    )>,
+    focused: Res<FocusedWidget>,
 ) {
-    for (interaction, palette, mut background) in &mut palette_query {
-        *background = match interaction {
+    for (entity, interaction, palette, mut background) in &mut palette_query {
+        if !interaction.is_changed() && !focused.is_changed() {
+            continue;
+        }
+        *background = match *interaction {
+            Interaction::None if focused.0 == Some(entity) => palette.focused,
             Interaction::None => palette.none,
             Interaction::Hovered => palette.hovered,
             Interaction::Pressed => palette.pressed,
@@ -65,9 +76,9 @@ fn apply_interaction_palette(
 #[derive(Resource, Asset, Reflect, Clone)]
 pub(crate) struct InteractionAssets {
     #[dependency]
-    hover: Handle<AudioSample>,
+    pub(super) hover: Handle<AudioSample>,
     #[dependency]
-    press: Handle<AudioSample>,
+    pub(super) press: Handle<AudioSample>,
 }
 
 impl InteractionAssets {
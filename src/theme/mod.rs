@@ -4,6 +4,7 @@
 #![allow(dead_code)]
 
 pub(crate) mod interaction;
+pub(crate) mod navigation;
 pub(crate) mod palette;
 pub(crate) mod widget;
 
@@ -15,5 +16,5 @@ pub(crate) mod prelude {
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(interaction::plugin);
+    app.add_plugins((interaction::plugin, navigation::plugin));
 }
@@ -0,0 +1,266 @@
+//! Keyboard and gamepad navigation for menus.
+//!
+//! The [`FocusedWidget`] is moved with the arrow keys, the D-pad or the left stick to the closest widget in that
+//! direction, so that any layout, e.g. the settings grid, can be navigated without describing it. Confirming clicks the
+//! focused widget, and [`back_just_pressed`] is the shared condition for leaving a menu. When a menu opens, its top
+//! left widget is focused. UI that is shown outside of a [`Menu`], e.g. the reader, is navigable while it has a
+//! [`NavigableOverlay`].
+
+use std::time::Duration;
+
+use bevy::{
+    camera::NormalizedRenderTarget,
+    picking::{
+        backend::HitData,
+        pointer::{Location, PointerButton, PointerId},
+    },
+    prelude::*,
+    window::{PrimaryWindow, WindowRef},
+};
+use bevy_seedling::sample::SamplePlayer;
+
+use crate::{
+    PostPhysicsAppSystems, audio::SfxPool, menus::Menu, theme::interaction::InteractionAssets,
+    ui_camera::UiCamera,
+};
+
+use super::interaction::InteractionPalette;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<FocusedWidget>();
+    app.init_resource::<FocusFirstWidget>();
+    app.add_systems(
+        Update,
+        (
+            clear_lost_focus,
+            request_first_widget_focus.run_if(state_changed::<Menu>.or(overlay_opened)),
+            focus_first_widget.run_if(can_navigate),
+            navigate.run_if(can_navigate),
+            confirm_focused_widget.run_if(can_navigate.and(confirm_just_pressed)),
+            play_focus_sound_effect.run_if(resource_changed::<FocusedWidget>),
+        )
+            .chain()
+            .run_if(resource_exists::<InteractionAssets>)
+            .in_set(PostPhysicsAppSystems::ChangeUi),
+    );
+}
+
+/// The widget that keyboard and gamepad input acts on.
+#[derive(Resource, Debug, Default, Reflect)]
+#[reflect(Resource)]
+pub(crate) struct FocusedWidget(pub(crate) Option<Entity>);
+
+/// UI outside of a [`Menu`] that is navigable while it is open.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub(crate) struct NavigableOverlay;
+
+/// Whether the top left widget should be focused as soon as the widgets are laid out.
+#[derive(Resource, Debug, Default)]
+struct FocusFirstWidget(bool);
+
+/// Whether a menu or a [`NavigableOverlay`] is open.
+fn can_navigate(
+    menu: Option<Res<State<Menu>>>,
+    overlays: Query<(), With<NavigableOverlay>>,
+) -> bool {
+    menu.is_some_and(|menu| *menu.get() != Menu::None) || !overlays.is_empty()
+}
+
+fn overlay_opened(overlays: Query<(), Added<NavigableOverlay>>) -> bool {
+    !overlays.is_empty()
+}
+
+/// Whether the player wants to leave the current menu.
+pub(crate) fn back_just_pressed(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+) -> bool {
+    keyboard.just_pressed(KeyCode::Escape)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::East))
+}
+
+/// Like [`input_just_pressed`](bevy::input::common_conditions::input_just_pressed), but for any connected gamepad.
+pub(crate) fn gamepad_just_pressed(
+    button: GamepadButton,
+) -> impl FnMut(Query<&Gamepad>) -> bool + Clone {
+    move |gamepads: Query<&Gamepad>| gamepads.iter().any(|gamepad| gamepad.just_pressed(button))
+}
+
+fn confirm_just_pressed(keyboard: Res<ButtonInput<KeyCode>>, gamepads: Query<&Gamepad>) -> bool {
+    keyboard.any_just_pressed([KeyCode::Enter, KeyCode::Space])
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::South))
+}
+
+/// How far the left stick has to be tilted to move the focus.
+const STICK_THRESHOLD: f32 = 0.5;
+/// How much being off to the side counts against a widget compared to being further away in the direction of travel.
+const SIDEWAYS_PENALTY: f32 = 2.0;
+
+fn clear_lost_focus(
+    mut focused: ResMut<FocusedWidget>,
+    widgets: Query<&InheritedVisibility, With<InteractionPalette>>,
+) {
+    let lost = focused
+        .0
+        .is_some_and(|entity| !widgets.get(entity).is_ok_and(|visibility| visibility.get()));
+    if lost {
+        focused.0 = None;
+    }
+}
+
+fn request_first_widget_focus(
+    mut focused: ResMut<FocusedWidget>,
+    mut focus_first: ResMut<FocusFirstWidget>,
+) {
+    focused.0 = None;
+    focus_first.0 = true;
+}
+
+/// Waits for the widgets of the menu that was just opened to be laid out, since their position is unknown before.
+fn focus_first_widget(
+    mut focus_first: ResMut<FocusFirstWidget>,
+    widgets: Query<
+        (
+            Entity,
+            &UiGlobalTransform,
+            &ComputedNode,
+            &InheritedVisibility,
+        ),
+        With<InteractionPalette>,
+    >,
+    mut focused: ResMut<FocusedWidget>,
+) {
+    if !focus_first.0 {
+        return;
+    }
+    let first = top_left(
+        widgets
+            .iter()
+            .filter(|(_, _, node, visibility)| visibility.get() && node.size() != Vec2::ZERO)
+            .map(|(entity, transform, _, _)| (entity, transform.translation)),
+    );
+    if first.is_some() {
+        focused.0 = first;
+        focus_first.0 = false;
+    }
+}
+
+/// The widget closest to the top left, preferring widgets further up.
+fn top_left(widgets: impl Iterator<Item = (Entity, Vec2)>) -> Option<Entity> {
+    widgets
+        .min_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)))
+        .map(|(entity, _)| entity)
+}
+
+fn navigate(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    widgets: Query<(Entity, &UiGlobalTransform, &InheritedVisibility), With<InteractionPalette>>,
+    mut focused: ResMut<FocusedWidget>,
+    mut stick_was_tilted: Local<bool>,
+) {
+    // In UI coordinates, y points down.
+    let mut direction = Vec2::ZERO;
+    for (key, button, step) in [
+        (KeyCode::ArrowUp, GamepadButton::DPadUp, Vec2::NEG_Y),
+        (KeyCode::ArrowDown, GamepadButton::DPadDown, Vec2::Y),
+        (KeyCode::ArrowLeft, GamepadButton::DPadLeft, Vec2::NEG_X),
+        (KeyCode::ArrowRight, GamepadButton::DPadRight, Vec2::X),
+    ] {
+        if keyboard.just_pressed(key) || gamepads.iter().any(|gamepad| gamepad.just_pressed(button))
+        {
+            direction = step;
+        }
+    }
+    let stick = gamepads
+        .iter()
+        .map(|gamepad| gamepad.left_stick())
+        .find(|stick| stick.length() >= STICK_THRESHOLD);
+    // Only move once per tilt of the stick.
+    if let (Some(stick), false) = (stick, *stick_was_tilted) {
+        direction = if stick.x.abs() > stick.y.abs() {
+            Vec2::X * stick.x.signum()
+        } else {
+            // The stick's y points up.
+            Vec2::NEG_Y * stick.y.signum()
+        };
+    }
+    *stick_was_tilted = stick.is_some();
+    if direction == Vec2::ZERO {
+        return;
+    }
+
+    let visible = || {
+        widgets
+            .iter()
+            .filter(|(_, _, visibility)| visibility.get())
+            .map(|(entity, transform, _)| (entity, transform.translation))
+    };
+    let Some(current) = focused.0.and_then(|entity| widgets.get(entity).ok()) else {
+        focused.0 = top_left(visible());
+        return;
+    };
+    let (current_entity, current_position) = (current.0, current.1.translation);
+
+    let next = visible()
+        .filter(|(entity, _)| *entity != current_entity)
+        .filter_map(|(entity, position)| {
+            let offset = position - current_position;
+            let along = offset.dot(direction);
+            if along <= 0.0 {
+                return None;
+            }
+            let sideways = (offset - direction * along).length();
+            Some((entity, along + sideways * SIDEWAYS_PENALTY))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b));
+    if let Some((entity, _)) = next {
+        focused.0 = Some(entity);
+    }
+}
+
+/// Clicks the focused widget, so that its regular [`Pointer<Click>`] action runs.
+fn confirm_focused_widget(
+    focused: Res<FocusedWidget>,
+    widgets: Query<(&UiGlobalTransform, &ComputedNode)>,
+    window: Single<Entity, With<PrimaryWindow>>,
+    camera: Single<Entity, With<UiCamera>>,
+    interaction_assets: Res<InteractionAssets>,
+    mut commands: Commands,
+) {
+    let Some(entity) = focused.0 else {
+        return;
+    };
+    let Ok((transform, node)) = widgets.get(entity) else {
+        return;
+    };
+    let Some(window) = WindowRef::Primary.normalize(Some(*window)) else {
+        return;
+    };
+    let location = Location {
+        target: NormalizedRenderTarget::Window(window),
+        position: transform.translation * node.inverse_scale_factor(),
+    };
+    let click = Click {
+        button: PointerButton::Primary,
+        hit: HitData::new(*camera, 0.0, None, None),
+        duration: Duration::ZERO,
+    };
+    commands.trigger(Pointer::new(PointerId::Mouse, location, click, entity));
+    commands.spawn((SamplePlayer::new(interaction_assets.press.clone()), SfxPool));
+}
+
+fn play_focus_sound_effect(
+    focused: Res<FocusedWidget>,
+    interaction_assets: Res<InteractionAssets>,
+    mut commands: Commands,
+) {
+    if focused.0.is_some() {
+        commands.spawn((SamplePlayer::new(interaction_assets.hover.clone()), SfxPool));
+    }
+}
@@ -14,6 +14,8 @@ pub(crate) const BUTTON_BACKGROUND: Color = Color::srgb(0.275, 0.400, 0.750);
 pub(crate) const BUTTON_HOVERED_BACKGROUND: Color = Color::srgb(0.384, 0.600, 0.820);
 // #3d4999
 pub(crate) const BUTTON_PRESSED_BACKGROUND: Color = Color::srgb(0.239, 0.286, 0.600);
+/// #7fb0e0
+pub(crate) const BUTTON_FOCUSED_BACKGROUND: Color = Color::srgb(0.498, 0.690, 0.878);
 
 /// #2b2c2f, taken from the Bevy website
 pub(crate) const SCREEN_BACKGROUND: Color = Color::srgb(0.16862746, 0.17254902, 0.18431373);
@@ -125,6 +125,7 @@ where
                         none: BUTTON_BACKGROUND,
                         hovered: BUTTON_HOVERED_BACKGROUND,
                         pressed: BUTTON_PRESSED_BACKGROUND,
+                        focused: BUTTON_FOCUSED_BACKGROUND,
                     },
                     children![(
                         Name::new("Button Text"),
@@ -1,37 +1,86 @@
 //! [Bevy TrenchBroom](https://github.com/Noxmore/bevy_trenchbroom) is the integration layer between Bevy and [TrenchBroom](https://trenchbroom.github.io/).
 //! We use TrenchBroom to edit our levels.
+//!
+//! - `--check-trenchbroom-config` (native dev builds): starts the game, checks that `--export-trenchbroom-config`
+//!   writes the same config as the game's classes would and exits with an error if it doesn't.
 
+#[cfg(feature = "dev")]
+use bevy::reflect::TypeRegistry;
 use bevy::{ecs::world::DeferredWorld, image::ImageSampler, prelude::*};
+#[cfg(feature = "dev")]
+use bevy_trenchbroom::class::{QuakeClassType, ReflectQuakeClass};
 use bevy_trenchbroom::prelude::*;
 use bevy_trenchbroom_avian::AvianPhysicsBackend;
 
 use crate::asset_processing::default_image_sampler_descriptor;
+#[cfg(feature = "dev")]
+use crate::{
+    props::manifest::{GenericProp, PropManifest, PropManifestAssets},
+    screens::Screen,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
-        TrenchBroomPlugins(
-            TrenchBroomConfig::new("jam")
-                .texture_extensions(to_string_vec(&["png", "jpg", "jpeg"]))
-                .texture_exclusions(to_string_vec(&[
-                    "*_disp_*",
-                    "*_arm_*",
-                    "*_nor_*",
-                    "*_local",
-                    "*_normal",
-                    "*_roughness",
-                ]))
-                .texture_sampler(texture_sampler())
-                .default_solid_scene_hooks(|| {
-                    SceneHooks::new()
-                        .convex_collider()
-                        .smooth_by_default_angle()
-                }),
-        )
-        .build()
-        // Fix issue with textures
-        .disable::<bevy_trenchbroom::config::ConfigPlugin>(),
+        TrenchBroomPlugins(trenchbroom_config())
+            .build()
+            // Fix issue with textures. The game config is exported with `--export-trenchbroom-config` instead.
+            .disable::<bevy_trenchbroom::config::ConfigPlugin>(),
         TrenchBroomPhysicsPlugin::new(AvianPhysicsBackend),
     ));
+    #[cfg(feature = "dev_native")]
+    if std::env::args().any(|arg| arg == "--check-trenchbroom-config") {
+        app.add_systems(Startup, check_exported_config);
+    }
+}
+
+/// The exported config is written from the classes the game registers without running any plugins.
+/// Make sure that it matches the one written from the running game, which is what `ConfigPlugin` would have written.
+#[cfg(feature = "dev_native")]
+fn check_exported_config(registry: Res<AppTypeRegistry>, mut app_exit: MessageWriter<AppExit>) {
+    match crate::cli::exported_config_differences(&registry.read()) {
+        Ok(differences) if differences.is_empty() => {
+            info!("The exported TrenchBroom config matches the game's classes");
+            app_exit.write(AppExit::Success);
+        }
+        Ok(differences) => {
+            error!(
+                "The config written by `--export-trenchbroom-config` differs from the game's classes in {}",
+                differences.join(", ")
+            );
+            app_exit.write(AppExit::error());
+        }
+        Err(error) => {
+            error!("Failed to compare the exported TrenchBroom config: {error}");
+            app_exit.write(AppExit::error());
+        }
+    }
+}
+
+/// The file extensions of the textures TrenchBroom lists.
+pub(crate) const TEXTURE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg"];
+
+/// Patterns of texture names TrenchBroom doesn't list. These are the normal, roughness and other maps that are
+/// applied together with the base color texture of the same name instead of being textures of their own.
+pub(crate) const TEXTURE_EXCLUSIONS: &[&str] = &[
+    "*_disp_*",
+    "*_arm_*",
+    "*_nor_*",
+    "*_local",
+    "*_normal",
+    "*_roughness",
+];
+
+/// The configuration used both for loading maps and for the game config exported to TrenchBroom.
+pub(crate) fn trenchbroom_config() -> TrenchBroomConfig {
+    TrenchBroomConfig::new("jam")
+        .texture_extensions(to_string_vec(TEXTURE_EXTENSIONS))
+        .texture_exclusions(to_string_vec(TEXTURE_EXCLUSIONS))
+        .texture_sampler(texture_sampler())
+        .default_solid_scene_hooks(|| {
+            SceneHooks::new()
+                .convex_collider()
+                .smooth_by_default_angle()
+        })
 }
 
 fn texture_sampler() -> ImageSampler {
@@ -76,3 +125,97 @@ impl LoadTrenchbroomModel for AssetServer {
         self.load(T::scene_path())
     }
 }
+
+/// Returns the class names of all point classes registered with TrenchBroom, sorted alphabetically.
+/// The classes of the [`PropManifest`] are listed instead of [`GenericProp`].
+#[cfg(feature = "dev")]
+pub(crate) fn point_class_names(
+    registry: &TypeRegistry,
+    props: Option<&PropManifest>,
+) -> Vec<String> {
+    let mut names: Vec<_> = registry
+        .iter_with_data::<ReflectQuakeClass>()
+        .map(|(_, class)| &class.erased_class.info)
+        .filter(|info| {
+            matches!(info.ty, QuakeClassType::Point) && info.name != GenericProp::CLASS_INFO.name
+        })
+        .map(|info| info.name.to_string())
+        .chain(
+            props
+                .into_iter()
+                .flat_map(|props| props.iter().map(|prop| prop.classname.clone())),
+        )
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+/// Returns the path of the model TrenchBroom displays for the given point class, if it has one.
+#[cfg(feature = "dev")]
+pub(crate) fn point_class_model_path(
+    registry: &TypeRegistry,
+    props: Option<&PropManifest>,
+    classname: &str,
+) -> Option<String> {
+    if let Some(prop) = props.and_then(|props| props.get(classname)) {
+        return Some(prop.model.clone());
+    }
+    registry
+        .iter_with_data::<ReflectQuakeClass>()
+        .map(|(_, class)| &class.erased_class.info)
+        .find(|info| matches!(info.ty, QuakeClassType::Point) && info.name == classname)
+        .and_then(|info| info.model_path())
+        .map(|path| path.to_string())
+}
+
+/// Spawns the point class with the given class name at `transform`, just like it would be spawned when placed in a map.
+/// This means that the `On<Add>` observers of the class run as usual. Classes from the [`PropManifest`] are
+/// spawned as a [`GenericProp`] of that class.
+#[cfg(feature = "dev")]
+pub(crate) fn spawn_point_class(
+    world: &mut World,
+    classname: &str,
+    transform: Transform,
+) -> Result<Entity> {
+    let is_prop_class = world
+        .get_resource::<PropManifestAssets>()
+        .and_then(|assets| assets.get(world.resource::<Assets<PropManifest>>()))
+        .is_some_and(|props| props.get(classname).is_some());
+    if is_prop_class {
+        let entity = world.spawn((
+            Name::new(classname.to_string()),
+            transform,
+            DespawnOnExit(Screen::Gameplay),
+            GenericProp {
+                kind: classname.to_string(),
+            },
+        ));
+        return Ok(entity.id());
+    }
+
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    let registration = registry
+        .iter_with_data::<ReflectQuakeClass>()
+        .find(|(_, class)| {
+            let info = &class.erased_class.info;
+            matches!(info.ty, QuakeClassType::Point) && info.name == classname
+        })
+        .map(|(registration, _)| registration)
+        .ok_or_else(|| format!("No point class named \"{classname}\" is registered"))?;
+    let reflect_default = registration
+        .data::<ReflectDefault>()
+        .ok_or_else(|| format!("Class \"{classname}\" does not reflect `Default`"))?;
+    let reflect_component = registration
+        .data::<ReflectComponent>()
+        .ok_or_else(|| format!("Class \"{classname}\" does not reflect `Component`"))?;
+
+    let class = reflect_default.default();
+    let mut entity = world.spawn((
+        Name::new(classname.to_string()),
+        transform,
+        DespawnOnExit(Screen::Gameplay),
+    ));
+    reflect_component.insert(&mut entity, class.as_partial_reflect(), &registry);
+    Ok(entity.id())
+}
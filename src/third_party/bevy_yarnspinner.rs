@@ -6,14 +6,22 @@ use bevy_trenchbroom::prelude::*;
 use bevy_yarnspinner::{events::DialogueCompleted, prelude::*};
 use bevy_yarnspinner_example_dialogue_view::prelude::*;
 
-use crate::screens::Screen;
+use crate::{menus::Menu, screens::Screen};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         // In Wasm, we need to load the dialogue file manually. If we're not targeting Wasm, we can just use `YarnSpinnerPlugin::default()` instead.
-        YarnSpinnerPlugin::with_yarn_sources(vec![YarnFileSource::file("dialogue/npc.yarn")]),
+        YarnSpinnerPlugin::with_yarn_sources(vec![
+            YarnFileSource::file("dialogue/npc.yarn"),
+            YarnFileSource::file("dialogue/barks.yarn"),
+        ]),
         ExampleYarnSpinnerDialogueViewPlugin::default(),
     ));
+    // Menus shown on top of a conversation, e.g. the dialogue history, shouldn't advance it.
+    app.configure_sets(
+        Update,
+        ExampleYarnSpinnerDialogueViewSystemSet.run_if(in_state(Menu::None)),
+    );
     app.add_systems(OnEnter(Screen::Gameplay), setup_dialogue_runner);
     app.add_systems(
         OnExit(Screen::Gameplay),
@@ -51,6 +59,8 @@ pub(crate) struct YarnNode {
     #[class(must_set)]
     pub(crate) yarn_node: String,
     pub(crate) prompt: String,
+    /// How the camera frames the speaker while the dialogue is running.
+    pub(crate) shot: DialogueShot,
 }
 
 impl YarnNode {
@@ -67,6 +77,19 @@ impl Default for YarnNode {
         Self {
             yarn_node: "".to_string(),
             prompt: "Talk".to_string(),
+            shot: DialogueShot::default(),
         }
     }
 }
+
+/// A camera framing preset for a [`YarnNode`]. Players can turn all framing off with the reduced motion setting.
+#[derive(FgdType, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DialogueShot {
+    /// Leave the camera where the player put it.
+    #[default]
+    None,
+    /// Frame the speaker's face from up close.
+    CloseUp,
+    /// Look at the speaker from slightly behind and to the side of the player.
+    OverTheShoulder,
+}